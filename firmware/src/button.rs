@@ -0,0 +1,83 @@
+//! Buttons sampled by the timer interrupt
+//!
+//! Sampling slower than the contacts bounce debounces them, the changes are
+//! queued to the main task as events.
+
+use arduino_uno::prelude::*;
+
+use crate::hw_config::{BtnBrightness, BtnHour, BtnMin};
+use crate::timebase::{self, Duration, Instant, Timer};
+use crate::util::Producer;
+
+/// Capacity of the event queue
+pub const EVENTS: usize = 8;
+
+const SAMPLE_PERIOD: Duration = Duration::from_millis(20);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Button {
+    Brightness,
+    Min,
+    Hour,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    Pressed(Button),
+    Released(Button),
+}
+
+pub struct Buttons {
+    brightness: BtnBrightness,
+    min: BtnMin,
+    hour: BtnHour,
+    pressed: [bool; 3],
+    timer: Timer,
+}
+
+impl Buttons {
+    pub fn new(brightness: BtnBrightness, min: BtnMin, hour: BtnHour) -> Self {
+        let mut timer = Timer::new();
+        timer.start_periodic(timebase::now(), SAMPLE_PERIOD);
+        let mut buttons = Self {
+            brightness,
+            min,
+            hour,
+            pressed: [false; 3],
+            timer,
+        };
+        // Buttons held at the start are no events
+        buttons.pressed = buttons.sample();
+        buttons
+    }
+
+    fn sample(&self) -> [bool; 3] {
+        [
+            self.brightness.is_high().void_unwrap(),
+            self.min.is_high().void_unwrap(),
+            self.hour.is_high().void_unwrap(),
+        ]
+    }
+
+    /// Samples the buttons once per period and queues their changes
+    pub fn poll(&mut self, now: Instant, events: &mut Producer<'static, Event, EVENTS>) {
+        if !self.timer.poll(now) {
+            return;
+        }
+        let pressed = self.sample();
+        let buttons = [Button::Brightness, Button::Min, Button::Hour];
+        for (index, button) in buttons.iter().enumerate() {
+            if pressed[index] == self.pressed[index] {
+                continue;
+            }
+            let event = if pressed[index] {
+                Event::Pressed(*button)
+            } else {
+                Event::Released(*button)
+            };
+            // The queue counts the events it has no room for
+            let _ = events.push(event);
+        }
+        self.pressed = pressed;
+    }
+}
@@ -0,0 +1,19 @@
+//! Persistence of the clock configuration in the EEPROM
+
+use wordclock_core::config::{Config, SIZE};
+
+use crate::eeprom::Eeprom;
+
+/// Start of the configuration in the EEPROM
+const ADDRESS: u16 = 0;
+
+/// Loads the configuration, the defaults are used if none was stored yet
+pub fn load(eeprom: &Eeprom) -> Config {
+    let mut bytes = [0; SIZE];
+    eeprom.read(ADDRESS, &mut bytes);
+    Config::from_bytes(&bytes).unwrap_or_default()
+}
+
+pub fn store(eeprom: &mut Eeprom, config: &Config) {
+    eeprom.write(ADDRESS, &config.to_bytes());
+}
@@ -0,0 +1,55 @@
+//! Access to the internal EEPROM of the atmega328p
+
+use avr_device::atmega328p::EEPROM;
+
+pub struct Eeprom {
+    eeprom: EEPROM,
+}
+
+impl Eeprom {
+    pub fn new(eeprom: EEPROM) -> Self {
+        Self { eeprom }
+    }
+
+    /// Reads `data.len()` bytes starting at the address
+    pub fn read(&self, address: u16, data: &mut [u8]) {
+        for (offset, byte) in data.iter_mut().enumerate() {
+            *byte = self.read_byte(address + offset as u16);
+        }
+    }
+
+    /// Writes the data starting at the address, bytes that already hold the
+    /// value are skipped to save write cycles
+    pub fn write(&mut self, address: u16, data: &[u8]) {
+        for (offset, byte) in data.iter().enumerate() {
+            let address = address + offset as u16;
+            if self.read_byte(address) != *byte {
+                self.write_byte(address, *byte);
+            }
+        }
+    }
+
+    fn wait_ready(&self) {
+        while self.eeprom.eecr.read().eepe().bit_is_set() {}
+    }
+
+    fn read_byte(&self, address: u16) -> u8 {
+        self.wait_ready();
+        self.eeprom.eear.write(|w| unsafe { w.bits(address) });
+        self.eeprom.eecr.write(|w| w.eere().set_bit());
+        self.eeprom.eedr.read().bits()
+    }
+
+    fn write_byte(&mut self, address: u16, byte: u8) {
+        self.wait_ready();
+        self.eeprom.eear.write(|w| unsafe { w.bits(address) });
+        self.eeprom.eedr.write(|w| unsafe { w.bits(byte) });
+        // EEPE has to be set within four cycles after EEMPE
+        avr_device::interrupt::free(|_| {
+            self.eeprom.eecr.write(|w| w.eempe().set_bit());
+            self.eeprom
+                .eecr
+                .write(|w| w.eempe().set_bit().eepe().set_bit());
+        });
+    }
+}
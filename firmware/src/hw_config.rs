@@ -0,0 +1,89 @@
+use arduino_uno::{
+    hal::port::{
+        mode::{Analog, Floating, Input, Output, PullUp},
+        portb::*,
+        portc::*,
+        portd::*,
+    },
+    Delay, I2cMaster, Serial,
+};
+
+use crate::timebase::Instant;
+use crate::util::Consumer;
+
+pub struct Resources {
+    pub rtc: crate::source::Rtc,
+    pub rtc_sqw_pin: RtcSqwPin,
+    pub led_on_board: LedOnBoard,
+    pub ldr_pin: LdrPin,
+    pub display: Display,
+    pub serial: SerialUsb,
+    pub serial_rx: SerialRx,
+    pub dcf77_edges: Dcf77Edges,
+    pub button_events: ButtonEvents,
+    pub eeprom: crate::eeprom::Eeprom,
+}
+
+pub type LdrPin = PC3<Analog>;
+
+pub type Dcf77Pin = PB1<Input<PullUp>>;
+
+pub type LedGreen = PD4<Output>;
+pub type LedYellow = PB0<Output>;
+pub type LedOnBoard = PB5<Output>;
+
+#[cfg(not(feature = "ds3231"))]
+pub type Rtc = wordclock_core::rtc::Ds1307<I2cMaster<Input<PullUp>>>;
+#[cfg(feature = "ds3231")]
+pub type Rtc = wordclock_core::rtc::Ds3231<I2cMaster<Input<PullUp>>>;
+pub type RtcSqwPin = PD2<Input<Floating>>;
+
+pub type ShiftregClock = PB4<Output>;
+pub type ShiftregLatch = PB3<Output>;
+pub type ShiftregData = PB2<Output>;
+pub type ShiftregOutputEnable = PD3<Output>;
+
+pub type Display = wordclock_core::display::Display<
+    ShiftregLatch,
+    ShiftregClock,
+    ShiftregData,
+    ShiftregOutputEnable,
+    Delay,
+>;
+
+#[cfg(all(feature = "english", feature = "dutch"))]
+compile_error!("select only one front plate language");
+
+#[cfg(not(any(feature = "english", feature = "dutch")))]
+pub type Language = wordclock_core::language::German;
+#[cfg(feature = "english")]
+pub type Language = wordclock_core::language::English;
+#[cfg(feature = "dutch")]
+pub type Language = wordclock_core::language::Dutch;
+
+/// Front plate language with the settings of the configuration applied
+#[cfg(not(any(feature = "english", feature = "dutch")))]
+pub fn language(config: &wordclock_core::config::Config) -> Language {
+    Language {
+        dialect: config.dialect,
+    }
+}
+#[cfg(any(feature = "english", feature = "dutch"))]
+pub fn language(_config: &wordclock_core::config::Config) -> Language {
+    Language::default()
+}
+
+pub type BtnBrightness = PD7<Input<Floating>>;
+pub type BtnMin = PD6<Input<Floating>>;
+pub type BtnHour = PD5<Input<Floating>>;
+
+pub type SerialUsb = Serial<Floating>;
+
+// Queues from the interrupts to main
+pub const SERIAL_RX: usize = 32;
+pub type SerialRx = Consumer<'static, u8, SERIAL_RX>;
+/// Level of the dcf77 pin after the edge and its time
+pub type Dcf77Edge = (bool, Instant);
+pub const DCF77_EDGES: usize = 16;
+pub type Dcf77Edges = Consumer<'static, Dcf77Edge, DCF77_EDGES>;
+pub type ButtonEvents = Consumer<'static, crate::button::Event, { crate::button::EVENTS }>;
@@ -0,0 +1,525 @@
+#![no_std]
+#![no_main]
+#![feature(abi_avr_interrupt)]
+#![feature(cell_update)]
+
+// Pull in the panic handler from panic-halt
+extern crate panic_halt;
+
+use core::cell::RefCell;
+use core::time::Duration;
+
+use arduino_uno::prelude::*;
+use avr_device::interrupt::{free, Mutex};
+use button::{Button, Event};
+use util::{Producer, Queue, SharedInput, SharedOutput};
+use wordclock_core::command::{Command, LineReader, Setting};
+use wordclock_core::config::Config;
+use wordclock_core::dcf77::stats::Stats;
+use wordclock_core::drift::Drift;
+use wordclock_core::phrase::{self, Phrase};
+use wordclock_core::rtc::Rtc;
+use wordclock_core::seconds::SecondCounter;
+use wordclock_core::source::{Arbiter, Manual, TimeSource};
+use wordclock_core::time::{Time, TimeError};
+
+mod button;
+mod config;
+mod eeprom;
+mod hw_config;
+mod source;
+mod timebase;
+mod util;
+
+// Resources init in main and used in interrupt
+static DCF77_PIN: Mutex<RefCell<Option<hw_config::Dcf77Pin>>> = Mutex::new(RefCell::new(None));
+static LED_GREEN: Mutex<RefCell<Option<hw_config::LedGreen>>> = Mutex::new(RefCell::new(None));
+static LED_YELLOW: Mutex<RefCell<Option<hw_config::LedYellow>>> = Mutex::new(RefCell::new(None));
+
+static BUTTONS: Mutex<RefCell<Option<button::Buttons>>> = Mutex::new(RefCell::new(None));
+
+// Queues from the interrupts to main, the interrupts keep the producers
+static mut DCF77_QUEUE: Queue<hw_config::Dcf77Edge, { hw_config::DCF77_EDGES }> = Queue::new();
+static DCF77_EDGES: Mutex<
+    RefCell<Option<Producer<'static, hw_config::Dcf77Edge, { hw_config::DCF77_EDGES }>>>,
+> = Mutex::new(RefCell::new(None));
+static mut BUTTON_QUEUE: Queue<Event, { button::EVENTS }> = Queue::new();
+static BUTTON_EVENTS: Mutex<RefCell<Option<Producer<'static, Event, { button::EVENTS }>>>> =
+    Mutex::new(RefCell::new(None));
+static mut SERIAL_QUEUE: Queue<u8, { hw_config::SERIAL_RX }> = Queue::new();
+static SERIAL_RX: Mutex<RefCell<Option<Producer<'static, u8, { hw_config::SERIAL_RX }>>>> =
+    Mutex::new(RefCell::new(None));
+
+// Seconds counted by the SQW signal, or by the timer while it is stopped
+static SECOND_COUNTER: Mutex<RefCell<SecondCounter>> =
+    Mutex::new(RefCell::new(SecondCounter::new()));
+
+// While DCF77 or the buttons set the time within this age, worse sources may
+// not move the clock by more than the jump
+const MAX_SOURCE_AGE: Duration = Duration::from_secs(2 * 60 * 60);
+const MAX_JUMP: Duration = Duration::from_secs(2 * 60);
+
+#[arduino_uno::entry]
+fn main() -> ! {
+    let mut resources = setup();
+    unsafe { avr_device::interrupt::enable() };
+
+    let mut btn_birghtness_held = false;
+
+    // Clear Display
+    resources.display.clear();
+    resources.display.enable_output();
+
+    // Settings and phrasing of the front plate
+    let mut config = config::load(&resources.eeprom);
+    let mut language = hw_config::language(&config);
+    let mut line_reader = LineReader::new();
+    // Set when the settings were changed while the brightness btn was held
+    let mut btn_settings_used = false;
+
+    // Time sources besides the rtc in the resources
+    let mut dcf77 = source::Dcf77::new(config.frames, MAX_JUMP);
+    let mut manual = Manual::default();
+    let mut arbiter = Arbiter::new(MAX_SOURCE_AGE, MAX_JUMP);
+
+    // Unknown until the first source provides the time
+    let mut current_time: Option<Time> = None;
+    resources.display.show_not_set();
+    let mut square_wave_running = false;
+    // Signal quality shown in the corners while the antenna is aligned
+    let mut signal_overlay = false;
+    let mut shown_bars = None;
+
+    loop {
+        // Update dcf77 Decoder Struct
+        while let Some((high, at)) = resources.dcf77_edges.pop() {
+            // eval Edge, the led shows if the last frame could be decoded
+            match dcf77.on_edge(high, at, current_time.as_ref()) {
+                Some(true) => resources.led_on_board.set_high().void_unwrap(),
+                Some(false) => resources.led_on_board.set_low().void_unwrap(),
+                None => (),
+            }
+        }
+        let lost = resources.dcf77_edges.take_overflows();
+        log_overflows(&mut resources.serial, "dcf77", lost);
+
+        // Report when the timer takes over counting the seconds from the rtc
+        let running = free(|cs| SECOND_COUNTER.borrow(cs).borrow().square_wave_running());
+        if running != square_wave_running {
+            square_wave_running = running;
+            let counted_by = if running { "sqw" } else { "timer" };
+            ufmt::uwriteln!(&mut resources.serial, "seconds: {}\r", counted_by).void_unwrap();
+        }
+
+        // Read the rtc once per minute
+        let minute_passed = free(|cs| SECOND_COUNTER.borrow(cs).borrow().seconds()) >= 60;
+        if minute_passed {
+            resources.rtc.request();
+        }
+
+        let serial = &mut resources.serial;
+        let new_time = arbiter.update(
+            &current_time.unwrap_or_default(),
+            &mut [&mut manual, &mut dcf77, &mut resources.rtc],
+            |source, err| log_time_error(serial, source, err),
+        );
+        if let Some(time) = new_time {
+            // Keep the time of the better sources in the rtc
+            let source = arbiter.source();
+            let stored = if source == Some(dcf77.name()) {
+                resources.rtc.sync(&time)
+            } else if source == Some(manual.name()) {
+                resources.rtc.set(&time)
+            } else {
+                Ok(())
+            };
+            if let Err(err) = stored {
+                log_time_error(&mut resources.serial, resources.rtc.name(), err);
+            }
+            current_time = Some(time);
+        } else if minute_passed {
+            // Keep the time in software while the rtc cannot be read
+            current_time = current_time.map(|time| time.inc_minutes());
+        }
+        // Signal quality for the corners while the antenna is aligned
+        let bars = if signal_overlay {
+            Some(dcf77.stats().bars(timebase::now()))
+        } else {
+            None
+        };
+        // Local time in the zone announced by DCF77, so it changes at the
+        // announced minute even without reception
+        let local = current_time.map(|time| dcf77.local(&time));
+        if new_time.is_some() || minute_passed {
+            // Reset the seconds counter
+            let seconds = current_time.map_or(0, |time| time.seconds());
+            free(|cs| SECOND_COUNTER.borrow(cs).borrow_mut().set(seconds));
+            // Update the display with the current time
+            show_time(&mut resources, &language, &config, local.as_ref(), bars);
+            shown_bars = bars;
+        }
+
+        let mut config_changed = false;
+
+        // Persist new estimates of the rtc drift and the time they are
+        // measured from
+        let drift = &resources.rtc.drift;
+        if drift.tenth_ppm() != config.drift || drift.base() != config.drift_base {
+            config.drift = drift.tenth_ppm();
+            config.drift_base = drift.base();
+            config_changed = true;
+        }
+
+        // The brightness btn steps the brightness when it is released, not
+        // when it is pressed, so it can be held for the settings. While it is
+        // held the min btn switches through the german dialects instead and
+        // the hour btn is ignored. Releasing it after a setting was changed
+        // keeps the brightness.
+        while let Some(event) = resources.button_events.pop() {
+            match event {
+                Event::Pressed(Button::Brightness) => btn_birghtness_held = true,
+                Event::Released(Button::Brightness) => {
+                    btn_birghtness_held = false;
+                    if !btn_settings_used {
+                        let current_brightness = resources.display.brightness();
+                        match current_brightness {
+                            0 => {
+                                resources.display.enable_output();
+                                resources.display.set_brightness(51);
+                            }
+                            255 => {
+                                resources.display.disable_output();
+                                resources.display.set_brightness(0);
+                            }
+                            _ => {
+                                resources
+                                    .display
+                                    .set_brightness(current_brightness.saturating_add(51));
+                            }
+                        }
+                    }
+                    btn_settings_used = false;
+                }
+                Event::Pressed(Button::Min) if btn_birghtness_held => {
+                    config.dialect = config.dialect.next();
+                    config_changed = true;
+                    btn_settings_used = true;
+                }
+                // Check min / hour btn, the time is set on the next update
+                Event::Pressed(Button::Min) => {
+                    manual.set(current_time.unwrap_or_default().inc_minutes());
+                }
+                Event::Pressed(Button::Hour) if !btn_birghtness_held => {
+                    manual.set(current_time.unwrap_or_default().inc_hours());
+                }
+                _ => (),
+            }
+        }
+        let lost = resources.button_events.take_overflows();
+        log_overflows(&mut resources.serial, "buttons", lost);
+
+        // Handle commands from the serial interface
+        while let Some(byte) = resources.serial_rx.pop() {
+            let line = match line_reader.push(byte) {
+                Some(line) if !line.is_empty() => line,
+                _ => continue,
+            };
+            match Command::parse(line) {
+                Some(Command::Show) => {
+                    for setting in Setting::all(&config).iter() {
+                        ufmt::uwriteln!(
+                            &mut resources.serial,
+                            "{} {}\r",
+                            setting.key(),
+                            setting.value()
+                        )
+                        .void_unwrap();
+                    }
+                }
+                Some(Command::Set(setting)) => {
+                    setting.apply(&mut config);
+                    config_changed = true;
+                    ufmt::uwriteln!(&mut resources.serial, "ok\r").void_unwrap();
+                }
+                Some(Command::Stats) => {
+                    log_stats(&mut resources.serial, dcf77.stats(), timebase::now());
+                }
+                Some(Command::Signal(on)) => {
+                    signal_overlay = on;
+                    ufmt::uwriteln!(&mut resources.serial, "ok\r").void_unwrap();
+                }
+                None => {
+                    ufmt::uwriteln!(&mut resources.serial, "error\r").void_unwrap();
+                }
+            }
+        }
+        let lost = resources.serial_rx.take_overflows();
+        log_overflows(&mut resources.serial, "serial", lost);
+
+        if config_changed {
+            config::store(&mut resources.eeprom, &config);
+            language = hw_config::language(&config);
+            dcf77.set_frames(config.frames);
+        }
+        if config_changed || bars != shown_bars {
+            show_time(&mut resources, &language, &config, local.as_ref(), bars);
+            shown_bars = bars;
+        }
+
+        // Update the Display
+        resources.display.update_display();
+    }
+}
+
+/// Every Rising Edge of the square wave signal of the rtc die is counted
+/// With this counter we can determin when it is time to update the display (every 60s)
+#[avr_device::interrupt(atmega328p)]
+fn INT0() {
+    free(|cs| {
+        LED_YELLOW.toggle(cs).void_unwrap();
+        SECOND_COUNTER.borrow(cs).borrow_mut().on_square_wave();
+    });
+}
+
+/// Cyclic Function that is called every milli second
+/// Every Cycle the timebase advances by one milli second
+/// The seconds are counted here as well once the square wave stopped
+#[avr_device::interrupt(atmega328p)]
+fn TIMER0_COMPA() {
+    free(|cs| {
+        let now = timebase::tick(cs);
+        SECOND_COUNTER.borrow(cs).borrow_mut().on_millisecond();
+
+        let mut buttons = BUTTONS.borrow(cs).borrow_mut();
+        let mut events = BUTTON_EVENTS.borrow(cs).borrow_mut();
+        if let (Some(buttons), Some(events)) = (buttons.as_mut(), events.as_mut()) {
+            buttons.poll(now, events);
+        }
+    });
+}
+
+/// Every edge of the dcf77 reciver is timestamped and queued to the main task
+#[avr_device::interrupt(atmega328p)]
+fn PCINT0() {
+    free(|cs| {
+        let dcf77_pin_state = DCF77_PIN.is_high(cs).void_unwrap();
+
+        LED_GREEN.set(cs, dcf77_pin_state).void_unwrap();
+
+        // Queue the edge, the queue counts the edges it has no room for
+        if let Some(edges) = DCF77_EDGES.borrow(cs).borrow_mut().as_mut() {
+            let _ = edges.push((dcf77_pin_state, timebase::now()));
+        }
+    });
+}
+
+/// Every received byte is queued to the main task
+#[avr_device::interrupt(atmega328p)]
+fn USART_RX() {
+    // Reading the data register clears the interrupt
+    let usart = unsafe { &*avr_device::atmega328p::USART0::ptr() };
+    let byte = usart.udr0.read().bits();
+    free(|cs| {
+        if let Some(rx) = SERIAL_RX.borrow(cs).borrow_mut().as_mut() {
+            let _ = rx.push(byte);
+        }
+    });
+}
+
+/// Shows the phrase for the local time and logs it to the serial interface,
+/// or that the time is not set yet
+///
+/// With bars of the signal quality the corners show them instead.
+fn show_time(
+    resources: &mut hw_config::Resources,
+    language: &hw_config::Language,
+    config: &Config,
+    local: Option<&Time>,
+    bars: Option<u8>,
+) {
+    let mut phrase = match (local, bars) {
+        (Some(local), _) => phrase::phrase(language, local, config.rounding, config.corners),
+        (None, Some(_)) => Phrase::new(),
+        (None, None) => {
+            resources.display.show_not_set();
+            ufmt::uwriteln!(&mut resources.serial, "time not set\r").void_unwrap();
+            return;
+        }
+    };
+    if let Some(bars) = bars {
+        phrase.corners = bars as i8;
+    }
+    resources.display.show(&phrase);
+    log_phrase(&mut resources.serial, &phrase);
+}
+
+/// Writes the words of the phrase and the minutes shown in the corners as one
+/// line, negative minutes are before the phrase
+fn log_phrase(serial: &mut hw_config::SerialUsb, phrase: &Phrase) {
+    for word in phrase.words() {
+        ufmt::uwrite!(serial, "{} ", word.letters).void_unwrap();
+    }
+    if phrase.corners >= 0 {
+        ufmt::uwrite!(serial, "+").void_unwrap();
+    }
+    ufmt::uwriteln!(serial, "{}\r", phrase.corners).void_unwrap();
+}
+
+/// Reports why a time source failed
+fn log_time_error(serial: &mut hw_config::SerialUsb, source: &str, err: TimeError) {
+    ufmt::uwriteln!(serial, "{}: {}\r", source, err.as_str()).void_unwrap();
+}
+
+/// Writes the statistics of the DCF77 reception, the pulses are those of the
+/// last complete minute
+fn log_stats(serial: &mut hw_config::SerialUsb, stats: &Stats, now: timebase::Instant) {
+    ufmt::uwriteln!(serial, "bars {}\r", stats.bars(now)).void_unwrap();
+    ufmt::uwriteln!(
+        serial,
+        "frames {} failed {}\r",
+        stats.frames(),
+        stats.failed()
+    )
+    .void_unwrap();
+    let call = if stats.call_bit() { "set" } else { "clear" };
+    ufmt::uwriteln!(serial, "call bit {}\r", call).void_unwrap();
+    let minute = stats.last_minute();
+    ufmt::uwrite!(
+        serial,
+        "pulses {} invalid {} noise {}",
+        minute.pulses,
+        minute.invalid,
+        minute.noise
+    )
+    .void_unwrap();
+    if let Some(rate) = minute.bit_error_rate() {
+        ufmt::uwrite!(serial, " errors {}/1000", rate).void_unwrap();
+    }
+    ufmt::uwrite!(serial, "\r\nwidths").void_unwrap();
+    for count in minute.histogram.iter() {
+        ufmt::uwrite!(serial, " {}", *count).void_unwrap();
+    }
+    ufmt::uwriteln!(serial, "\r").void_unwrap();
+    match stats.since_sync(now) {
+        Some(since) => ufmt::uwriteln!(serial, "sync {}s ago\r", since.as_millis() / 1000),
+        None => ufmt::uwriteln!(serial, "sync never\r"),
+    }
+    .void_unwrap();
+}
+
+/// Reports items an interrupt had no room for in its queue
+fn log_overflows(serial: &mut hw_config::SerialUsb, queue: &str, lost: u8) {
+    if lost > 0 {
+        ufmt::uwriteln!(serial, "{}: {} lost\r", queue, lost).void_unwrap();
+    }
+}
+
+fn setup() -> hw_config::Resources {
+    // Take peripherals
+    let dp = arduino_uno::Peripherals::take().unwrap();
+
+    // Init IOs
+    let pins = arduino_uno::Pins::new(dp.PORTB, dp.PORTC, dp.PORTD);
+
+    // Init serial interface
+    let mut serial = arduino_uno::Serial::new(
+        dp.USART0,
+        pins.d0,
+        pins.d1.into_output(&pins.ddr),
+        57600.into_baudrate(),
+    );
+    // Receive with interrupt, the bytes are queued to main
+    let (producer, serial_rx) = unsafe { SERIAL_QUEUE.split() };
+    free(|cs| SERIAL_RX.borrow(cs).replace(Some(producer)));
+    let usart = unsafe { &*avr_device::atmega328p::USART0::ptr() };
+    usart.ucsr0b.modify(|_, w| w.rxcie0().set_bit());
+
+    // Init i2c master
+    let i2c = arduino_uno::I2cMaster::new(
+        dp.TWI,
+        pins.a4.into_pull_up_input(&pins.ddr),
+        pins.a5.into_pull_up_input(&pins.ddr),
+        50000,
+    );
+
+    // LED Pins
+    let led_green = Some(pins.d4.into_output(&pins.ddr));
+    free(|cs| LED_GREEN.borrow(cs).replace(led_green));
+    let led_yellow = Some(pins.d8.into_output(&pins.ddr));
+    free(|cs| LED_YELLOW.borrow(cs).replace(led_yellow));
+    let led_on_board = pins.d13.into_output(&pins.ddr);
+
+    // Pin with signal from dcf77
+    let dcf77_pin = Some(pins.d9.into_pull_up_input(&pins.ddr));
+    free(|cs| DCF77_PIN.borrow(cs).replace(dcf77_pin));
+    let (producer, dcf77_edges) = unsafe { DCF77_QUEUE.split() };
+    free(|cs| DCF77_EDGES.borrow(cs).replace(Some(producer)));
+    // Enable pin change interrupt on PB1 (PCINT1)
+    dp.EXINT.pcmsk0.write(|w| unsafe { w.bits(1 << 1) });
+    dp.EXINT.pcicr.write(|w| unsafe { w.bits(1 << 0) }); // PCIE0 covers PB0..PB7
+
+    // Init Light Depending Resistor
+    let adc_settings = arduino_uno::adc::AdcSettings::default();
+    let mut adc = arduino_uno::adc::Adc::new(dp.ADC, adc_settings);
+    let ldr_pin = pins.a3.into_analog_input(&mut adc);
+
+    // Shift Register
+    let shiftreg_clock = pins.d12.into_output(&pins.ddr);
+    let shiftreg_latch = pins.d11.into_output(&pins.ddr);
+    let shiftreg_data = pins.d10.into_output(&pins.ddr);
+    let shiftreg_output_enable = pins.d3.into_output(&pins.ddr);
+
+    let display = hw_config::Display::new(
+        shiftreg_latch,
+        shiftreg_clock,
+        shiftreg_data,
+        shiftreg_output_enable,
+        arduino_uno::Delay::new(),
+    );
+
+    // Buttons
+    let btn_birghtness = pins.d7.into_floating_input(&pins.ddr);
+    let btn_min = pins.d6.into_floating_input(&pins.ddr);
+    let btn_hour = pins.d5.into_floating_input(&pins.ddr);
+    // Sampled by the timer, which queues the presses and releases to main
+    let buttons = button::Buttons::new(btn_birghtness, btn_min, btn_hour);
+    free(|cs| BUTTONS.borrow(cs).replace(Some(buttons)));
+    let (producer, button_events) = unsafe { BUTTON_QUEUE.split() };
+    free(|cs| BUTTON_EVENTS.borrow(cs).replace(Some(producer)));
+
+    // Init rtc - ds1307 or ds3231
+    let mut rtc = hw_config::Rtc::new(i2c);
+    let rtc_sqw_pin = pins.d2.into_floating_input(&pins.ddr);
+    // Enable SQW Output, without it the timer counts the seconds
+    if let Err(err) = rtc.enable_square_wave() {
+        log_time_error(&mut serial, "rtc", err);
+    }
+    // Read the rtc 10s after the start
+    free(|cs| SECOND_COUNTER.borrow(cs).borrow_mut().set(50));
+    // Enable interrupt on sqw pin
+    dp.EXINT.eicra.write(|w| w.isc0().val_0x03()); // Rising Edge on INT0
+    dp.EXINT.eimsk.write(|w| w.int0().set_bit()); // Enable the Interrupt
+
+    // Create Timer with 1ms overflow for cyclic tasks
+    timebase::init(&dp.TC0);
+
+    // Internal EEPROM holding the configuration
+    let eeprom = eeprom::Eeprom::new(dp.EEPROM);
+    // The drift measured before the reset keeps correcting the rtc
+    let stored = config::load(&eeprom);
+    let drift = Drift::restore(stored.drift, stored.drift_base);
+
+    // Return the resources
+    hw_config::Resources {
+        rtc: source::Rtc::new(rtc, drift),
+        rtc_sqw_pin,
+        led_on_board,
+        ldr_pin,
+        display,
+        serial,
+        serial_rx,
+        dcf77_edges,
+        button_events,
+        eeprom,
+    }
+}
@@ -0,0 +1,190 @@
+//! Time sources on the board
+//!
+//! The buttons use `wordclock_core::source::Manual`, which beats both of
+//! these. DCF77 is preferred over the RTC, which only keeps the time between
+//! the receptions.
+
+use core::time::Duration;
+
+use wordclock_core::dcf77::schedule::ZoneSchedule;
+use wordclock_core::dcf77::stats::Stats;
+use wordclock_core::dcf77::validate::Validator;
+use wordclock_core::dcf77::Receiver;
+use wordclock_core::drift::Drift;
+use wordclock_core::rtc::Rtc as _;
+use wordclock_core::source::TimeSource;
+use wordclock_core::time::{Time, TimeError};
+use wordclock_core::timezone;
+
+use crate::timebase::Instant;
+
+/// The RTC is read once per minute when requested, its time is corrected by
+/// the drift measured against DCF77
+pub struct Rtc {
+    rtc: crate::hw_config::Rtc,
+    requested: bool,
+    last_valid: Option<Time>,
+    pub drift: Drift,
+}
+
+impl Rtc {
+    pub const QUALITY: u8 = 1;
+
+    /// `drift` is the one stored before the reset
+    pub fn new(rtc: crate::hw_config::Rtc, drift: Drift) -> Self {
+        Self {
+            rtc,
+            requested: true,
+            last_valid: None,
+            drift,
+        }
+    }
+
+    /// Read the time on the next poll
+    pub fn request(&mut self) {
+        self.requested = true;
+    }
+
+    /// Sets the time entered by the user
+    pub fn set(&mut self, time: &Time) -> Result<(), TimeError> {
+        self.rtc.set_time(time)?;
+        self.drift.set(time);
+        Ok(())
+    }
+
+    /// Keeps the RTC at the time of DCF77 and measures its drift
+    pub fn sync(&mut self, reference: &Time) -> Result<(), TimeError> {
+        let set = match self.rtc.time() {
+            Ok(rtc) => self.drift.sync(&rtc, reference),
+            Err(_) => {
+                self.drift.set(reference);
+                true
+            }
+        };
+        if set {
+            self.rtc.set_time(reference)?;
+        }
+        #[cfg(feature = "ds3231")]
+        self.compensate_aging(reference)?;
+        Ok(())
+    }
+
+    /// Moves the estimated drift to the aging offset, one step of it is
+    /// about 0.1 ppm. The drift is measured from the reference on.
+    #[cfg(feature = "ds3231")]
+    fn compensate_aging(&mut self, reference: &Time) -> Result<(), TimeError> {
+        let drift = self.drift.tenth_ppm();
+        if drift == 0 {
+            return Ok(());
+        }
+        let aging = i16::from(self.rtc.aging_offset()?) + drift;
+        let aging = aging.max(i16::from(i8::MIN)).min(i16::from(i8::MAX));
+        self.rtc.set_aging_offset(aging as i8)?;
+        self.drift.rebase(reference);
+        Ok(())
+    }
+}
+
+impl TimeSource for Rtc {
+    fn name(&self) -> &'static str {
+        "rtc"
+    }
+
+    fn poll(&mut self) -> Result<Option<Time>, TimeError> {
+        if !self.requested {
+            return Ok(None);
+        }
+        self.requested = false;
+        let time = self.drift.correct(&self.rtc.time()?);
+        self.last_valid = Some(time);
+        Ok(Some(time))
+    }
+
+    fn last_valid(&self) -> Option<Time> {
+        self.last_valid
+    }
+
+    fn quality(&self) -> u8 {
+        Self::QUALITY
+    }
+}
+
+/// Decodes the edges of the DCF77 receiver, a time is only provided once the
+/// validator confirmed it by consecutive frames
+pub struct Dcf77 {
+    receiver: Receiver,
+    validator: Validator,
+    schedule: ZoneSchedule,
+    pending: Option<Result<Time, TimeError>>,
+    last_valid: Option<Time>,
+}
+
+impl Dcf77 {
+    pub const QUALITY: u8 = 2;
+
+    /// `frames` consecutive frames confirm a time, more if it is further
+    /// than `max_jump` off the clock
+    pub fn new(frames: u8, max_jump: Duration) -> Self {
+        Self {
+            receiver: Receiver::new(),
+            validator: Validator::new(frames, max_jump),
+            schedule: ZoneSchedule::new(),
+            pending: None,
+            last_valid: None,
+        }
+    }
+
+    pub fn set_frames(&mut self, frames: u8) {
+        self.validator.set_required(frames);
+    }
+
+    /// Feeds an edge of the receiver, returns whether the frame could be
+    /// decoded at the end of a minute. `clock` is the time currently shown.
+    pub fn on_edge(&mut self, high: bool, at: Instant, clock: Option<&Time>) -> Option<bool> {
+        let frame = self.receiver.on_edge(high, at)?;
+
+        let decoded = self.receiver.decode(frame);
+        self.pending = self.validator.check(decoded, clock).transpose();
+        if let (Ok(frame), Some(Ok(time))) = (frame, self.pending) {
+            self.schedule.update(&frame, &time);
+            self.receiver.stats.synced(at);
+        }
+        Some(decoded.is_ok())
+    }
+
+    /// Local time of the UTC time, in the zone DCF77 sent or announced for it
+    /// and by the rules without recent frames
+    pub fn local(&self, utc: &Time) -> Time {
+        match self.schedule.zone(utc) {
+            Some(zone) => timezone::to_local_in(utc, zone),
+            None => timezone::to_local(utc),
+        }
+    }
+
+    /// Statistics of the reception
+    pub fn stats(&self) -> &Stats {
+        &self.receiver.stats
+    }
+}
+
+impl TimeSource for Dcf77 {
+    fn name(&self) -> &'static str {
+        "dcf77"
+    }
+
+    fn poll(&mut self) -> Result<Option<Time>, TimeError> {
+        let time = self.pending.take().transpose()?;
+        if time.is_some() {
+            self.last_valid = time;
+        }
+        Ok(time)
+    }
+
+    fn last_valid(&self) -> Option<Time> {
+        self.last_valid
+    }
+
+    fn quality(&self) -> u8 {
+        Self::QUALITY
+    }
+}
@@ -0,0 +1,33 @@
+//! Millisecond ticks of Timer0
+//!
+//! The types and software timers come from `wordclock_core::timebase`, this
+//! module only provides the counter behind `now()`.
+
+use core::cell::Cell;
+
+use avr_device::atmega328p::TC0;
+use avr_device::interrupt::{free, CriticalSection, Mutex};
+pub use wordclock_core::timebase::{Duration, Instant, Timer};
+
+static MILLIS: Mutex<Cell<u32>> = Mutex::new(Cell::new(0));
+
+/// Starts Timer0 with an interrupt every millisecond
+pub fn init(tc0: &TC0) {
+    // 16 MHz / 64 are 250 counts per ms, in CTC mode the timer counts from
+    // 0 up to and including OCR0A
+    tc0.tccr0a.write(|w| w.wgm0().ctc());
+    tc0.ocr0a.write(|w| unsafe { w.bits(249) });
+    tc0.tccr0b.write(|w| w.cs0().prescale_64());
+    tc0.timsk0.write(|w| w.ocie0a().set_bit());
+}
+
+/// Advances the counter and returns the new time, called by the Timer0
+/// interrupt
+pub fn tick(cs: &CriticalSection) -> Instant {
+    Instant::from_millis(MILLIS.borrow(cs).update(|millis| millis.wrapping_add(1)))
+}
+
+/// Milliseconds since the start
+pub fn now() -> Instant {
+    free(|cs| Instant::from_millis(MILLIS.borrow(cs).get()))
+}
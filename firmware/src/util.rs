@@ -1,6 +1,8 @@
 use core::cell::RefCell;
 
 use avr_device::interrupt::Mutex;
+/// Lock free queues from the interrupts to the main task
+pub use wordclock_core::queue::{Consumer, Producer, Queue};
 
 pub trait SetOutput<T>
 where
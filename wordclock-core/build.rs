@@ -0,0 +1,222 @@
+//! Generates the word constants of the front plates from `plates/*.txt`
+//!
+//! The description is validated while generating: every word has to match
+//! the letters of the grid, fit into the 11 columns and must not overlap
+//! other words of its row unless both declare to share the letters.
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+use std::process;
+
+const ROWS: usize = 10;
+const COLUMNS: usize = 11;
+
+struct Word {
+    name: String,
+    row: usize,
+    column: usize,
+    letters: String,
+    shares: Vec<String>,
+    line: usize,
+}
+
+impl Word {
+    fn len(&self) -> usize {
+        self.letters.chars().count()
+    }
+
+    /// Column 0 is the most significant bit of a row
+    fn mask(&self) -> u16 {
+        (self.column..self.column + self.len()).fold(0, |mask, column| mask | 0x8000 >> column)
+    }
+}
+
+struct Plate {
+    grid: Vec<Vec<char>>,
+    words: Vec<Word>,
+}
+
+fn parse(source: &str) -> Result<Plate, (usize, String)> {
+    enum Section {
+        None,
+        Grid,
+        Words,
+    }
+
+    let mut section = Section::None;
+    let mut grid = Vec::new();
+    let mut words = Vec::new();
+
+    for (index, line) in source.lines().enumerate() {
+        let number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match line {
+            "[grid]" => section = Section::Grid,
+            "[words]" => section = Section::Words,
+            _ => match section {
+                Section::None => return Err((number, "expected [grid] or [words]".into())),
+                Section::Grid => {
+                    let row: Vec<char> = line.chars().collect();
+                    if row.len() != COLUMNS {
+                        return Err((
+                            number,
+                            format!("row has {} letters, expected {}", row.len(), COLUMNS),
+                        ));
+                    }
+                    grid.push(row);
+                }
+                Section::Words => words.push(parse_word(line, number)?),
+            },
+        }
+    }
+
+    if grid.len() != ROWS {
+        return Err((
+            0,
+            format!("grid has {} rows, expected {}", grid.len(), ROWS),
+        ));
+    }
+
+    Ok(Plate { grid, words })
+}
+
+fn parse_word(line: &str, number: usize) -> Result<Word, (usize, String)> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    if fields.len() < 4 || (fields.len() > 4 && fields[4] != "shares") {
+        return Err((
+            number,
+            "expected NAME ROW COLUMN LETTERS [shares OTHER...], the letters of a word have to be contiguous"
+                .into(),
+        ));
+    }
+    let number_field = |field: &str, what: &str| {
+        field
+            .parse::<usize>()
+            .map_err(|_| (number, format!("invalid {} {:?}", what, field)))
+    };
+    let name = fields[0];
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+    {
+        return Err((number, format!("name {:?} is no valid constant", name)));
+    }
+    Ok(Word {
+        name: name.into(),
+        row: number_field(fields[1], "row")?,
+        column: number_field(fields[2], "column")?,
+        letters: fields[3].into(),
+        shares: fields.iter().skip(5).map(|name| name.to_string()).collect(),
+        line: number,
+    })
+}
+
+fn validate(plate: &Plate) -> Result<(), (usize, String)> {
+    for (index, word) in plate.words.iter().enumerate() {
+        if word.row >= ROWS {
+            return Err((
+                word.line,
+                format!("row {} is outside of the grid", word.row),
+            ));
+        }
+        if word.column + word.len() > COLUMNS {
+            return Err((
+                word.line,
+                format!("{} does not fit into the {} columns", word.name, COLUMNS),
+            ));
+        }
+        let grid: String = plate.grid[word.row][word.column..word.column + word.len()]
+            .iter()
+            .collect();
+        if grid != word.letters {
+            return Err((
+                word.line,
+                format!(
+                    "{} reads {:?} in the grid, expected {:?}",
+                    word.name, grid, word.letters
+                ),
+            ));
+        }
+        for other in &plate.words[..index] {
+            if other.name == word.name {
+                return Err((word.line, format!("{} is declared twice", word.name)));
+            }
+            let overlapping = other.row == word.row && other.mask() & word.mask() != 0;
+            let shared = word.shares.contains(&other.name) && other.shares.contains(&word.name);
+            if overlapping && !shared {
+                return Err((
+                    word.line,
+                    format!(
+                        "{} overlaps {} from line {}, declare `shares` on both if intended",
+                        word.name, other.name, other.line
+                    ),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn generate(plate: &Plate, source: &str) -> String {
+    let mut out = String::new();
+    writeln!(out, "// Generated by build.rs from {}", source).unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "/// Letters of the front plate, row by row").unwrap();
+    writeln!(out, "pub const LETTERS: [&str; {}] = [", ROWS).unwrap();
+    for row in &plate.grid {
+        writeln!(out, "    {:?},", row.iter().collect::<String>()).unwrap();
+    }
+    writeln!(out, "];").unwrap();
+
+    for word in &plate.words {
+        writeln!(out).unwrap();
+        writeln!(out, "pub const {}: Word = Word {{", word.name).unwrap();
+        writeln!(out, "    letters: {:?},", word.letters).unwrap();
+        writeln!(out, "    row: {},", word.row).unwrap();
+        writeln!(out, "    mask: 0b{:011b}_0000_0,", word.mask() >> 5).unwrap();
+        writeln!(out, "}};").unwrap();
+    }
+
+    writeln!(out).unwrap();
+    writeln!(out, "/// All words of the plate").unwrap();
+    writeln!(out, "pub const ALL: &[Word] = &[").unwrap();
+    for word in &plate.words {
+        writeln!(out, "    {},", word.name).unwrap();
+    }
+    writeln!(out, "];").unwrap();
+    out
+}
+
+/// Front plates, each one generates `<name>.rs` in `OUT_DIR`
+const PLATES: &[&str] = &["german", "english", "dutch"];
+
+fn main() {
+    let out_dir = env::var("OUT_DIR").unwrap();
+
+    for name in PLATES {
+        let source = format!("plates/{}.txt", name);
+        println!("cargo:rerun-if-changed={}", source);
+
+        let description = fs::read_to_string(&source).unwrap_or_else(|err| {
+            eprintln!("{}: {}", source, err);
+            process::exit(1);
+        });
+        let plate = parse(&description)
+            .and_then(|plate| validate(&plate).map(|_| plate))
+            .unwrap_or_else(|(line, message)| {
+                eprintln!("{}:{}: {}", source, line, message);
+                process::exit(1);
+            });
+
+        fs::write(
+            Path::new(&out_dir).join(format!("{}.rs", name)),
+            generate(&plate, &source),
+        )
+        .unwrap();
+    }
+}
@@ -0,0 +1,256 @@
+//! Commands received over the serial interface
+//!
+//! Every command is a line of ascii text:
+//!
+//! ```text
+//! show                              print the configuration
+//! set quarter nach-vor|dreiviertel  "viertel nach zwei" or "viertel drei"
+//! set twenty zwanzig|zehn-halb      "zwanzig nach zwei" or "zehn vor halb drei"
+//! set one eins|ein                  "eins uhr" or "ein uhr"
+//! set rounding floor|nearest        round the time down or to the nearest phrase
+//! set corners on|off                show the minutes between the phrases
+//! set frames 1..5                   consecutive DCF77 frames to take the time
+//! stats                             print the statistics of the DCF77 reception
+//! signal on|off                     show the DCF77 signal quality in the corners
+//! ```
+
+use crate::config::{Config, MAX_FRAMES, MIN_FRAMES};
+use crate::language::german::{One, Quarter, Twenty};
+use crate::phrase::Rounding;
+
+/// Longest line that is accepted
+pub const MAX_LINE: usize = 32;
+
+/// Values of `frames` from `MIN_FRAMES` to `MAX_FRAMES`
+const FRAMES: [&str; MAX_FRAMES as usize] = ["1", "2", "3", "4", "5"];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Setting {
+    Quarter(Quarter),
+    Twenty(Twenty),
+    One(One),
+    Rounding(Rounding),
+    Corners(bool),
+    Frames(u8),
+}
+
+impl Setting {
+    /// All settings as they are currently configured
+    pub fn all(config: &Config) -> [Setting; 6] {
+        [
+            Setting::Quarter(config.dialect.quarter),
+            Setting::Twenty(config.dialect.twenty),
+            Setting::One(config.dialect.one),
+            Setting::Rounding(config.rounding),
+            Setting::Corners(config.corners),
+            Setting::Frames(config.frames),
+        ]
+    }
+
+    /// Name of the setting as used by `set`
+    pub fn key(self) -> &'static str {
+        match self {
+            Setting::Quarter(_) => "quarter",
+            Setting::Twenty(_) => "twenty",
+            Setting::One(_) => "one",
+            Setting::Rounding(_) => "rounding",
+            Setting::Corners(_) => "corners",
+            Setting::Frames(_) => "frames",
+        }
+    }
+
+    /// Value of the setting as used by `set`
+    pub fn value(self) -> &'static str {
+        match self {
+            Setting::Quarter(Quarter::NachVor) => "nach-vor",
+            Setting::Quarter(Quarter::Dreiviertel) => "dreiviertel",
+            Setting::Twenty(Twenty::Zwanzig) => "zwanzig",
+            Setting::Twenty(Twenty::ZehnHalb) => "zehn-halb",
+            Setting::One(One::Eins) => "eins",
+            Setting::One(One::Ein) => "ein",
+            Setting::Rounding(Rounding::Floor) => "floor",
+            Setting::Rounding(Rounding::Nearest) => "nearest",
+            Setting::Corners(true) => "on",
+            Setting::Corners(false) => "off",
+            Setting::Frames(frames) => {
+                match FRAMES.get(usize::from(frames.wrapping_sub(MIN_FRAMES))) {
+                    Some(value) => value,
+                    None => "?",
+                }
+            }
+        }
+    }
+
+    /// Applies the setting to the configuration
+    pub fn apply(self, config: &mut Config) {
+        match self {
+            Setting::Quarter(quarter) => config.dialect.quarter = quarter,
+            Setting::Twenty(twenty) => config.dialect.twenty = twenty,
+            Setting::One(one) => config.dialect.one = one,
+            Setting::Rounding(rounding) => config.rounding = rounding,
+            Setting::Corners(corners) => config.corners = corners,
+            Setting::Frames(frames) => config.frames = frames,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Command {
+    Show,
+    Set(Setting),
+    Stats,
+    /// Corners show the signal quality instead of the minutes, e.g. while
+    /// aligning the antenna
+    Signal(bool),
+}
+
+impl Command {
+    pub fn parse(line: &[u8]) -> Option<Self> {
+        let line = core::str::from_utf8(line).ok()?;
+        let mut words = line.split_ascii_whitespace();
+        let command = match (words.next()?, words.next(), words.next()) {
+            ("show", None, None) => Command::Show,
+            ("set", Some(key), Some(value)) => Command::Set(parse_setting(key, value)?),
+            ("stats", None, None) => Command::Stats,
+            ("signal", Some("on"), None) => Command::Signal(true),
+            ("signal", Some("off"), None) => Command::Signal(false),
+            _ => return None,
+        };
+        if words.next().is_some() {
+            return None;
+        }
+        Some(command)
+    }
+}
+
+fn parse_setting(key: &str, value: &str) -> Option<Setting> {
+    Some(match (key, value) {
+        ("quarter", "nach-vor") => Setting::Quarter(Quarter::NachVor),
+        ("quarter", "dreiviertel") => Setting::Quarter(Quarter::Dreiviertel),
+        ("twenty", "zwanzig") => Setting::Twenty(Twenty::Zwanzig),
+        ("twenty", "zehn-halb") => Setting::Twenty(Twenty::ZehnHalb),
+        ("one", "eins") => Setting::One(One::Eins),
+        ("one", "ein") => Setting::One(One::Ein),
+        ("rounding", "floor") => Setting::Rounding(Rounding::Floor),
+        ("rounding", "nearest") => Setting::Rounding(Rounding::Nearest),
+        ("corners", "on") => Setting::Corners(true),
+        ("corners", "off") => Setting::Corners(false),
+        ("frames", value) => {
+            let index = FRAMES.iter().position(|frames| *frames == value)?;
+            Setting::Frames(MIN_FRAMES + index as u8)
+        }
+        _ => return None,
+    })
+}
+
+/// Collects received bytes into lines
+#[derive(Default)]
+pub struct LineReader {
+    buffer: [u8; MAX_LINE],
+    len: usize,
+    overflow: bool,
+}
+
+impl LineReader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a byte, returns the line once its end is received. Lines longer
+    /// than `MAX_LINE` are returned empty so they fail to parse.
+    pub fn push(&mut self, byte: u8) -> Option<&[u8]> {
+        match byte {
+            b'\r' | b'\n' => {
+                let len = if self.overflow { 0 } else { self.len };
+                self.len = 0;
+                self.overflow = false;
+                Some(&self.buffer[..len])
+            }
+            _ if self.len < MAX_LINE => {
+                self.buffer[self.len] = byte;
+                self.len += 1;
+                None
+            }
+            _ => {
+                self.overflow = true;
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_commands() {
+        assert_eq!(Command::parse(b"show"), Some(Command::Show));
+        assert_eq!(
+            Command::parse(b"  set quarter dreiviertel "),
+            Some(Command::Set(Setting::Quarter(Quarter::Dreiviertel)))
+        );
+        assert_eq!(
+            Command::parse(b"set one ein"),
+            Some(Command::Set(Setting::One(One::Ein)))
+        );
+        assert_eq!(Command::parse(b"set one"), None);
+        assert_eq!(Command::parse(b"set one zwei"), None);
+        assert_eq!(
+            Command::parse(b"set frames 3"),
+            Some(Command::Set(Setting::Frames(3)))
+        );
+        assert_eq!(Command::parse(b"set frames 0"), None);
+        assert_eq!(Command::parse(b"set frames 6"), None);
+        assert_eq!(Command::parse(b"show all"), None);
+        assert_eq!(Command::parse(b"stats"), Some(Command::Stats));
+        assert_eq!(Command::parse(b"signal on"), Some(Command::Signal(true)));
+        assert_eq!(Command::parse(b"signal off"), Some(Command::Signal(false)));
+        assert_eq!(Command::parse(b"signal"), None);
+        assert_eq!(Command::parse(b""), None);
+    }
+
+    #[test]
+    fn shown_settings_parse() {
+        let mut config = Config::default();
+        for _ in 0..8 {
+            for setting in Setting::all(&config).iter() {
+                let line = format!("set {} {}", setting.key(), setting.value());
+                assert_eq!(
+                    Command::parse(line.as_bytes()),
+                    Some(Command::Set(*setting))
+                );
+            }
+            config.dialect = config.dialect.next();
+            config.rounding = Rounding::Nearest;
+            config.corners = !config.corners;
+            config.frames = config.frames % MAX_FRAMES + 1;
+        }
+    }
+
+    #[test]
+    fn line_reader_splits_lines() {
+        let mut reader = LineReader::new();
+        let mut lines = Vec::new();
+        for byte in b"show\r\nset one ein\n" {
+            if let Some(line) = reader.push(*byte) {
+                lines.push(line.to_vec());
+            }
+        }
+        assert_eq!(
+            lines,
+            [b"show".to_vec(), b"".to_vec(), b"set one ein".to_vec()]
+        );
+    }
+
+    #[test]
+    fn line_reader_drops_long_lines() {
+        let mut reader = LineReader::new();
+        for _ in 0..MAX_LINE + 5 {
+            assert_eq!(reader.push(b'x'), None);
+        }
+        assert_eq!(reader.push(b'\n'), Some(&b""[..]));
+        reader.push(b'a');
+        assert_eq!(reader.push(b'\n'), Some(&b"a"[..]));
+    }
+}
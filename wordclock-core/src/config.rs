@@ -0,0 +1,193 @@
+//! Settings of the clock that survive a power loss
+//!
+//! The firmware keeps the serialized form in the EEPROM. A magic byte, the
+//! format version and a checksum guard against erased or corrupted memory,
+//! in which case the defaults are used.
+
+use crate::drift::Base;
+use crate::language::german::Dialect;
+use crate::phrase::Rounding;
+use crate::time::Time;
+
+/// Size of the serialized configuration in bytes
+pub const SIZE: usize = 12;
+
+const MAGIC: u8 = 0x57;
+const VERSION: u8 = 2;
+/// Size of the first version, before the drift base was stored
+const SIZE_V1: usize = 8;
+/// Timestamp of a missing drift base, it is after 2099
+const NO_BASE: u32 = u32::MAX;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Config {
+    /// Phrasing of the german front plate
+    pub dialect: Dialect,
+    /// Rounding of the time to the five minute phrases
+    pub rounding: Rounding,
+    /// Show the minutes between the phrases with the corner LEDs
+    pub corners: bool,
+    /// Drift of the RTC measured against DCF77 in tenths of ppm
+    pub drift: i16,
+    /// Time the RTC was set to last, the drift is measured from it
+    pub drift_base: Option<Base>,
+    /// Consecutive DCF77 frames needed before the time is taken
+    pub frames: u8,
+}
+
+/// Range of `frames`, values outside of it are restored as the default
+pub const MIN_FRAMES: u8 = 1;
+pub const MAX_FRAMES: u8 = 5;
+const DEFAULT_FRAMES: u8 = 2;
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            dialect: Dialect::default(),
+            rounding: Rounding::Floor,
+            corners: true,
+            drift: 0,
+            drift_base: None,
+            frames: DEFAULT_FRAMES,
+        }
+    }
+}
+
+// Flags of the display byte, cleared flags are the defaults
+const NEAREST: u8 = 0b01;
+const NO_CORNERS: u8 = 0b10;
+const DRIFT_REFERENCE: u8 = 0b100;
+
+impl Config {
+    pub fn to_bytes(&self) -> [u8; SIZE] {
+        let mut bytes = [0; SIZE];
+        bytes[0] = MAGIC;
+        bytes[1] = VERSION;
+        bytes[2] = self.dialect.to_bits();
+        if self.rounding == Rounding::Nearest {
+            bytes[3] |= NEAREST;
+        }
+        if !self.corners {
+            bytes[3] |= NO_CORNERS;
+        }
+        bytes[4..6].copy_from_slice(&self.drift.to_le_bytes());
+        bytes[6] = self.frames;
+        let base = match self.drift_base {
+            Some(base) => {
+                if base.reference {
+                    bytes[3] |= DRIFT_REFERENCE;
+                }
+                base.time.timestamp()
+            }
+            None => NO_BASE,
+        };
+        bytes[7..11].copy_from_slice(&base.to_le_bytes());
+        bytes[SIZE - 1] = checksum(&bytes[..SIZE - 1]);
+        bytes
+    }
+
+    /// Restores the configuration, `None` if the bytes hold no valid one
+    ///
+    /// The first version is still read, without a drift base.
+    pub fn from_bytes(bytes: &[u8; SIZE]) -> Option<Self> {
+        let size = match bytes[1] {
+            1 => SIZE_V1,
+            VERSION => SIZE,
+            _ => return None,
+        };
+        if bytes[0] != MAGIC || checksum(&bytes[..size - 1]) != bytes[size - 1] {
+            return None;
+        }
+        let drift_base = match bytes[1] {
+            VERSION => {
+                let timestamp = u32::from_le_bytes([bytes[7], bytes[8], bytes[9], bytes[10]]);
+                Time::from_timestamp(timestamp).map(|time| Base {
+                    time,
+                    reference: bytes[3] & DRIFT_REFERENCE != 0,
+                })
+            }
+            _ => None,
+        };
+        Some(Self {
+            dialect: Dialect::from_bits(bytes[2]),
+            rounding: if bytes[3] & NEAREST != 0 {
+                Rounding::Nearest
+            } else {
+                Rounding::Floor
+            },
+            corners: bytes[3] & NO_CORNERS == 0,
+            drift: i16::from_le_bytes([bytes[4], bytes[5]]),
+            drift_base,
+            frames: match bytes[6] {
+                frames @ MIN_FRAMES..=MAX_FRAMES => frames,
+                _ => DEFAULT_FRAMES,
+            },
+        })
+    }
+}
+
+fn checksum(bytes: &[u8]) -> u8 {
+    // Erased EEPROM reads 0xff, start with a value that does not match it
+    bytes
+        .iter()
+        .fold(0xa5, |sum: u8, byte| sum.rotate_left(1) ^ byte)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::language::german::{One, Quarter, Twenty};
+
+    #[test]
+    fn round_trip() {
+        let config = Config {
+            dialect: Dialect {
+                quarter: Quarter::Dreiviertel,
+                twenty: Twenty::Zwanzig,
+                one: One::Ein,
+            },
+            rounding: Rounding::Nearest,
+            corners: false,
+            drift: -123,
+            drift_base: Some(Base {
+                time: Time::new(2021, 3, 1, 12, 34, 56).unwrap(),
+                reference: true,
+            }),
+            frames: 4,
+        };
+        assert_eq!(Config::from_bytes(&config.to_bytes()), Some(config));
+    }
+
+    #[test]
+    fn reads_the_first_version() {
+        let mut bytes = [0xff; SIZE];
+        bytes[..SIZE_V1].copy_from_slice(&[MAGIC, 1, 0, NEAREST, 0x85, 0xff, 3, 0]);
+        bytes[SIZE_V1 - 1] = checksum(&bytes[..SIZE_V1 - 1]);
+        let config = Config::from_bytes(&bytes).unwrap();
+        assert_eq!(config.rounding, Rounding::Nearest);
+        assert_eq!(config.drift, -123);
+        assert_eq!(config.drift_base, None);
+        assert_eq!(config.frames, 3);
+    }
+
+    #[test]
+    fn frames_out_of_range_are_the_default() {
+        // Stored before the number of frames was configurable
+        let mut bytes = Config::default().to_bytes();
+        bytes[6] = 0;
+        bytes[SIZE - 1] = checksum(&bytes[..SIZE - 1]);
+        assert_eq!(Config::from_bytes(&bytes), Some(Config::default()));
+    }
+
+    #[test]
+    fn erased_memory_is_rejected() {
+        assert_eq!(Config::from_bytes(&[0xff; SIZE]), None);
+    }
+
+    #[test]
+    fn corrupted_byte_is_rejected() {
+        let mut bytes = Config::default().to_bytes();
+        bytes[2] ^= 0b010;
+        assert_eq!(Config::from_bytes(&bytes), None);
+    }
+}
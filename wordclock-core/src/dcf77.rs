@@ -0,0 +1,273 @@
+//! Reception of the DCF77 frames
+//!
+//! The frame holds one bit per second of the minute, bit n is the bit sent in
+//! second n. `pulse` turns the edges of the receiver into bits, which the
+//! `FrameAssembler` collects until the minute is complete. The `Receiver`
+//! combines both and keeps the `stats` of the reception, `validate` decides
+//! whether a frame can be trusted. `schedule` follows the announced changes
+//! of the zone.
+
+use crate::time::{Time, TimeError};
+use crate::timebase::Instant;
+use crate::timezone::Zone;
+
+pub mod pulse;
+pub mod schedule;
+pub mod stats;
+pub mod validate;
+
+use pulse::{Pulse, PulseDecoder};
+use stats::Stats;
+
+/// Bits sent per minute, there is no pulse in the 59th second
+const BITS: u8 = 59;
+/// Bits of a minute with a leap second, the leap second is sent as a 0
+const LEAP_BITS: u8 = BITS + 1;
+
+/// Call bit, set on irregularities of the transmitter
+const CALL: u32 = 15;
+/// A1, the zone changes at the end of the hour
+const ZONE_ANNOUNCED: u32 = 16;
+const CEST: u32 = 17;
+const CET: u32 = 18;
+/// A2, a leap second is inserted at the end of the hour
+const LEAP_ANNOUNCED: u32 = 19;
+
+/// Parity bit of a DCF77 frame
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Parity {
+    Minute,
+    Hour,
+    Date,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Frame(pub u64);
+
+impl Frame {
+    pub(crate) fn bit(&self, second: u32) -> bool {
+        self.0 >> second & 1 == 1
+    }
+
+    /// Zone of the transmitted local time, `None` unless exactly one of the
+    /// two zone bits is set
+    pub fn zone(&self) -> Option<Zone> {
+        match (self.bit(CEST), self.bit(CET)) {
+            (true, false) => Some(Zone::Cest),
+            (false, true) => Some(Zone::Cet),
+            _ => None,
+        }
+    }
+
+    /// The transmitter reports an irregularity, e.g. that it runs on the
+    /// backup antenna
+    pub fn call_bit(&self) -> bool {
+        self.bit(CALL)
+    }
+
+    /// The zone changes at the end of the hour
+    pub fn zone_announced(&self) -> bool {
+        self.bit(ZONE_ANNOUNCED)
+    }
+
+    /// A leap second is inserted at the end of the hour
+    pub fn leap_announced(&self) -> bool {
+        self.bit(LEAP_ANNOUNCED)
+    }
+}
+
+/// Collects the bits of a minute
+#[derive(Default)]
+pub struct FrameAssembler {
+    bits: u64,
+    count: u8,
+    /// No pulse of the minute was invalid, false until the first minute gap
+    valid: bool,
+    /// The last frame announced a leap second
+    leap: bool,
+}
+
+impl FrameAssembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a pulse, returns the frame once the minute is complete
+    ///
+    /// The bits before the first minute gap are incomplete, as are frames
+    /// with an invalid pulse. Their end reports `TimeError::Incomplete`. A
+    /// minute with a leap second is only complete once it was announced.
+    pub fn push(&mut self, pulse: Pulse) -> Option<Result<Frame, TimeError>> {
+        let bit = match pulse {
+            Pulse::Bit(bit) | Pulse::LastBit(bit) => bit,
+            Pulse::Invalid => {
+                self.valid = false;
+                false
+            }
+        };
+        if self.count < LEAP_BITS {
+            self.bits |= u64::from(bit) << self.count;
+        } else {
+            // The minute gap was missed
+            self.valid = false;
+        }
+        self.count = self.count.saturating_add(1);
+
+        if !matches!(pulse, Pulse::LastBit(_)) {
+            return None;
+        }
+        let frame = Frame(self.bits);
+        let complete = match self.count {
+            BITS => true,
+            LEAP_BITS => (self.leap || frame.leap_announced()) && !frame.bit(u32::from(BITS)),
+            _ => false,
+        };
+        if !complete || !self.valid {
+            self.restart();
+            return Some(Err(TimeError::Incomplete));
+        }
+        self.leap = frame.leap_announced();
+        self.restart();
+        Some(Ok(frame))
+    }
+
+    fn restart(&mut self) {
+        self.bits = 0;
+        self.count = 0;
+        self.valid = true;
+    }
+}
+
+/// Turns the edges of the receiver into frames
+#[derive(Default)]
+pub struct Receiver {
+    pulses: PulseDecoder,
+    frames: FrameAssembler,
+    pub stats: Stats,
+}
+
+impl Receiver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds an edge, `high` while the carrier is reduced, returns the frame
+    /// at each minute gap
+    pub fn on_edge(&mut self, high: bool, at: Instant) -> Option<Result<Frame, TimeError>> {
+        let noise = self.pulses.noise();
+        let pulse = self.pulses.on_edge(high, at);
+        self.stats.noise(self.pulses.noise().wrapping_sub(noise));
+
+        let pulse = pulse?;
+        self.stats.pulse(pulse, self.pulses.width(), at);
+        let frame = self.frames.push(pulse)?;
+        self.stats.minute(frame.as_ref().ok());
+        Some(frame)
+    }
+
+    /// Decodes the UTC time of a frame returned by `on_edge`, a complete
+    /// frame that fails the checks of `validate::decode` is counted as
+    /// rejected
+    pub fn decode(&mut self, frame: Result<Frame, TimeError>) -> Result<Time, TimeError> {
+        let decoded = validate::decode(&frame?);
+        if decoded.is_err() {
+            self.stats.rejected();
+        }
+        decoded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zone_bits() {
+        assert_eq!(Frame(1 << 17).zone(), Some(Zone::Cest));
+        assert_eq!(Frame(1 << 18 | 1 << 20).zone(), Some(Zone::Cet));
+        assert_eq!(Frame(1 << 17 | 1 << 18).zone(), None);
+        assert_eq!(Frame(1 << 20).zone(), None);
+    }
+
+    #[test]
+    fn announcements() {
+        let frame = Frame(1 << 15 | 1 << 16 | 1 << 20);
+        assert!(frame.call_bit());
+        assert!(frame.zone_announced());
+        assert!(!frame.leap_announced());
+        assert!(Frame(1 << 19).leap_announced());
+    }
+
+    fn push_bits(
+        assembler: &mut FrameAssembler,
+        bits: u64,
+        count: u8,
+    ) -> Option<Result<Frame, TimeError>> {
+        for second in 0..count - 1 {
+            assert_eq!(assembler.push(Pulse::Bit(bits >> second & 1 == 1)), None);
+        }
+        assembler.push(Pulse::LastBit(bits >> (count - 1) & 1 == 1))
+    }
+
+    #[test]
+    fn assembles_a_minute() {
+        let mut assembler = FrameAssembler::new();
+        // Started within a minute
+        assert_eq!(
+            push_bits(&mut assembler, 0, 20),
+            Some(Err(TimeError::Incomplete))
+        );
+        let bits = 0x0123_4567_89ab_cdef & ((1 << 59) - 1);
+        assert_eq!(push_bits(&mut assembler, bits, 59), Some(Ok(Frame(bits))));
+    }
+
+    #[test]
+    fn drops_invalid_minutes() {
+        let mut assembler = FrameAssembler::new();
+        push_bits(&mut assembler, 0, 1);
+        assembler.push(Pulse::Invalid);
+        assert_eq!(
+            push_bits(&mut assembler, 0, 58),
+            Some(Err(TimeError::Incomplete))
+        );
+        // Missed minute gap
+        assert_eq!(
+            push_bits(&mut assembler, 0, 62),
+            Some(Err(TimeError::Incomplete))
+        );
+        assert_eq!(
+            push_bits(&mut assembler, 1 << 20, 59),
+            Some(Ok(Frame(1 << 20)))
+        );
+    }
+
+    #[test]
+    fn leap_second_minutes() {
+        let mut assembler = FrameAssembler::new();
+        push_bits(&mut assembler, 0, 1);
+        // Not announced
+        assert_eq!(
+            push_bits(&mut assembler, 1 << 20, 60),
+            Some(Err(TimeError::Incomplete))
+        );
+        let announced = 1 << 19 | 1 << 20;
+        assert_eq!(
+            push_bits(&mut assembler, announced, 59),
+            Some(Ok(Frame(announced)))
+        );
+        // The leap second is always a 0
+        assert_eq!(
+            push_bits(&mut assembler, 1 << 59 | 1 << 20, 60),
+            Some(Err(TimeError::Incomplete))
+        );
+        // The frame of the leap second itself may already lack A2
+        assert_eq!(
+            push_bits(&mut assembler, 1 << 20, 60),
+            Some(Ok(Frame(1 << 20)))
+        );
+        assert_eq!(
+            push_bits(&mut assembler, 1 << 20, 60),
+            Some(Err(TimeError::Incomplete))
+        );
+    }
+}
@@ -0,0 +1,215 @@
+//! Classification of the pulses of the receiver by the timing of its edges
+//!
+//! Each second starts with a pulse, 100 ms for a 0 and 200 ms for a 1. The
+//! 59th second has no pulse, so the gap before the first second of the next
+//! minute is two seconds long. A pulse is only classified once the next one
+//! started, that way dropouts within a pulse can still be joined.
+
+use crate::timebase::{Duration, Instant};
+
+/// Edges closer than this are noise
+const GLITCH: Duration = Duration::from_millis(30);
+const ZERO_MIN: Duration = Duration::from_millis(60);
+const ZERO_MAX: Duration = Duration::from_millis(140);
+const ONE_MIN: Duration = Duration::from_millis(160);
+const ONE_MAX: Duration = Duration::from_millis(250);
+const SECOND_MIN: Duration = Duration::from_millis(900);
+const SECOND_MAX: Duration = Duration::from_millis(1100);
+const MINUTE_MIN: Duration = Duration::from_millis(1900);
+const MINUTE_MAX: Duration = Duration::from_millis(2100);
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Pulse {
+    /// Bit of a second, the next second followed
+    Bit(bool),
+    /// Bit of the 58th second, the next second starts a new minute
+    LastBit(bool),
+    /// Width or distance of the pulse outside the tolerance
+    Invalid,
+}
+
+/// Rising edge of a pulse and its falling edge once the level dropped
+#[derive(Clone, Copy, Debug)]
+struct Edges {
+    rise: Instant,
+    fall: Option<Instant>,
+}
+
+#[derive(Default)]
+pub struct PulseDecoder {
+    /// Last pulse that was long enough, not classified yet
+    pulse: Option<Edges>,
+    /// Rising edge that may start the next pulse
+    rise: Option<Instant>,
+    /// Width of the last classified pulse
+    width: Option<Duration>,
+    noise: u16,
+}
+
+impl PulseDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Edges rejected as noise since the start
+    pub fn noise(&self) -> u16 {
+        self.noise
+    }
+
+    /// Width of the last classified pulse, `None` if it never ended
+    pub fn width(&self) -> Option<Duration> {
+        self.width
+    }
+
+    /// Feeds an edge of the receiver, `high` while the carrier is reduced
+    pub fn on_edge(&mut self, high: bool, at: Instant) -> Option<Pulse> {
+        if high {
+            self.on_rise(at);
+            None
+        } else {
+            self.on_fall(at)
+        }
+    }
+
+    fn on_rise(&mut self, at: Instant) {
+        if let Some(pulse) = &mut self.pulse {
+            match pulse.fall {
+                // A dropout within the pulse
+                Some(fall) if at - fall < GLITCH => {
+                    pulse.fall = None;
+                    self.noise = self.noise.saturating_add(1);
+                    return;
+                }
+                // The pulse never ended, e.g. after an edge was missed
+                None => pulse.fall = Some(at),
+                _ => (),
+            }
+        }
+        self.rise = Some(at);
+    }
+
+    fn on_fall(&mut self, at: Instant) -> Option<Pulse> {
+        let rise = match self.rise.take() {
+            Some(rise) => rise,
+            None => {
+                // End of a pulse that had a dropout
+                if let Some(pulse) = &mut self.pulse {
+                    if pulse.fall.is_none() {
+                        pulse.fall = Some(at);
+                    }
+                }
+                return None;
+            }
+        };
+        let pulse = Edges {
+            rise,
+            fall: Some(at),
+        };
+        if at - rise < GLITCH {
+            self.noise = self.noise.saturating_add(1);
+            return None;
+        }
+
+        let previous = match self.pulse {
+            Some(previous) => previous,
+            None => {
+                self.pulse = Some(pulse);
+                return None;
+            }
+        };
+        let distance = rise - previous.rise;
+        if distance < SECOND_MIN {
+            // A spike between the pulses
+            self.noise = self.noise.saturating_add(1);
+            return None;
+        }
+        self.pulse = Some(pulse);
+        self.width = previous.fall.map(|fall| fall - previous.rise);
+        Some(classify(self.width, distance))
+    }
+}
+
+fn classify(width: Option<Duration>, distance: Duration) -> Pulse {
+    let width = match width {
+        Some(width) => width,
+        None => return Pulse::Invalid,
+    };
+    let bit = if ZERO_MIN <= width && width <= ZERO_MAX {
+        false
+    } else if ONE_MIN <= width && width <= ONE_MAX {
+        true
+    } else {
+        return Pulse::Invalid;
+    };
+    if SECOND_MIN <= distance && distance <= SECOND_MAX {
+        Pulse::Bit(bit)
+    } else if MINUTE_MIN <= distance && distance <= MINUTE_MAX {
+        Pulse::LastBit(bit)
+    } else {
+        Pulse::Invalid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(millis: u32) -> Instant {
+        Instant::from_millis(millis)
+    }
+
+    /// Feeds a pulse, returns what the decoder reported at its end
+    fn pulse(decoder: &mut PulseDecoder, rise: u32, width: u32) -> Option<Pulse> {
+        assert_eq!(decoder.on_edge(true, at(rise)), None);
+        decoder.on_edge(false, at(rise + width))
+    }
+
+    #[test]
+    fn classifies_zeros_and_ones() {
+        let mut decoder = PulseDecoder::new();
+        assert_eq!(pulse(&mut decoder, 0, 100), None);
+        assert_eq!(pulse(&mut decoder, 1000, 190), Some(Pulse::Bit(false)));
+        assert_eq!(pulse(&mut decoder, 2010, 80), Some(Pulse::Bit(true)));
+        assert_eq!(decoder.width(), Some(Duration::from_millis(190)));
+        assert_eq!(pulse(&mut decoder, 3000, 100), Some(Pulse::Bit(false)));
+    }
+
+    #[test]
+    fn detects_the_minute_gap() {
+        let mut decoder = PulseDecoder::new();
+        pulse(&mut decoder, 0, 200);
+        assert_eq!(pulse(&mut decoder, 2000, 100), Some(Pulse::LastBit(true)));
+    }
+
+    #[test]
+    fn out_of_tolerance() {
+        let mut decoder = PulseDecoder::new();
+        pulse(&mut decoder, 0, 150);
+        assert_eq!(pulse(&mut decoder, 1000, 100), Some(Pulse::Invalid));
+        assert_eq!(pulse(&mut decoder, 2500, 100), Some(Pulse::Invalid));
+        assert_eq!(pulse(&mut decoder, 3500, 100), Some(Pulse::Bit(false)));
+    }
+
+    #[test]
+    fn filters_spikes() {
+        let mut decoder = PulseDecoder::new();
+        pulse(&mut decoder, 0, 200);
+        // Short spike and a longer one between the pulses
+        assert_eq!(pulse(&mut decoder, 400, 5), None);
+        assert_eq!(pulse(&mut decoder, 600, 50), None);
+        assert_eq!(pulse(&mut decoder, 1000, 100), Some(Pulse::Bit(true)));
+        assert_eq!(decoder.noise(), 2);
+    }
+
+    #[test]
+    fn joins_dropouts_within_a_pulse() {
+        let mut decoder = PulseDecoder::new();
+        pulse(&mut decoder, 0, 100);
+        // 200 ms pulse with a 10 ms dropout after 60 ms
+        assert_eq!(pulse(&mut decoder, 1000, 60), Some(Pulse::Bit(false)));
+        assert_eq!(decoder.on_edge(true, at(1070)), None);
+        assert_eq!(decoder.on_edge(false, at(1200)), None);
+        assert_eq!(pulse(&mut decoder, 2000, 100), Some(Pulse::Bit(true)));
+        assert_eq!(decoder.noise(), 1);
+    }
+}
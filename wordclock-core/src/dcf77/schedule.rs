@@ -0,0 +1,123 @@
+//! Changes of the zone announced by DCF77
+//!
+//! A1 is sent during the hour before the zone changes, the change happens at
+//! the end of that hour. Once announced the clock switches at that minute,
+//! even if the frames after it are lost. The frame of the full hour after the
+//! change still carries A1, it is already sent in the new zone.
+
+use core::time::Duration;
+
+use super::Frame;
+use crate::time::Time;
+use crate::timezone::Zone;
+
+const HOUR: Duration = Duration::from_secs(60 * 60);
+/// The zone of a frame is used this long, afterwards the rules of
+/// `timezone` apply again
+const VALID: Duration = Duration::from_secs(2 * 60 * 60);
+
+#[derive(Default)]
+pub struct ZoneSchedule {
+    /// Zone of the last confirmed frame and its UTC time
+    received: Option<(Zone, Time)>,
+    /// Start of the next zone, if announced
+    change: Option<Time>,
+}
+
+impl ZoneSchedule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Takes the zone and the announcement of a confirmed frame of the UTC
+    /// time
+    pub fn update(&mut self, frame: &Frame, utc: &Time) {
+        let zone = match frame.zone() {
+            Some(zone) => zone,
+            None => return,
+        };
+        let changed = match self.received {
+            Some((received, _)) => received != zone,
+            None => false,
+        };
+        self.received = Some((zone, *utc));
+        self.change = if frame.zone_announced() && utc.minutes() != 0 && !changed {
+            next_hour(utc)
+        } else {
+            None
+        };
+    }
+
+    /// Zone sent or announced for the UTC time, `None` without a recent frame
+    pub fn zone(&self, utc: &Time) -> Option<Zone> {
+        let (zone, received) = self.received?;
+        if let Some(age) = utc.duration_since(&received) {
+            if age >= VALID {
+                return None;
+            }
+        }
+        match self.change {
+            Some(change) if *utc >= change => Some(match zone {
+                Zone::Cet => Zone::Cest,
+                Zone::Cest => Zone::Cet,
+            }),
+            _ => Some(zone),
+        }
+    }
+}
+
+fn next_hour(utc: &Time) -> Option<Time> {
+    let hour = Time::new(utc.year(), utc.month(), utc.day(), utc.hour(), 0, 0).ok()?;
+    hour.checked_add(HOUR)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(hour: u8, min: u8) -> Time {
+        Time::new(2021, 10, 31, hour, min, 0).unwrap()
+    }
+
+    #[test]
+    fn switches_at_the_announced_hour() {
+        let mut schedule = ZoneSchedule::new();
+        assert_eq!(schedule.zone(&utc(0, 30)), None);
+
+        schedule.update(&Frame(1 << 16 | 1 << 17), &utc(0, 30));
+        assert_eq!(schedule.zone(&utc(0, 59)), Some(Zone::Cest));
+        // No further frame was received
+        assert_eq!(schedule.zone(&utc(1, 0)), Some(Zone::Cet));
+        assert_eq!(schedule.zone(&utc(2, 29)), Some(Zone::Cet));
+        assert_eq!(schedule.zone(&utc(2, 30)), None);
+    }
+
+    #[test]
+    fn follows_the_frames() {
+        let mut schedule = ZoneSchedule::new();
+        schedule.update(&Frame(1 << 16 | 1 << 17), &utc(0, 59));
+        schedule.update(&Frame(1 << 18), &utc(1, 0));
+        assert_eq!(schedule.zone(&utc(1, 0)), Some(Zone::Cet));
+        assert_eq!(schedule.zone(&utc(1, 30)), Some(Zone::Cet));
+
+        // A1 is still set in the first frame of the new zone
+        schedule.update(&Frame(1 << 16 | 1 << 17), &utc(0, 59));
+        schedule.update(&Frame(1 << 16 | 1 << 18), &utc(1, 0));
+        assert_eq!(schedule.zone(&utc(1, 0)), Some(Zone::Cet));
+        assert_eq!(schedule.zone(&utc(2, 0)), Some(Zone::Cet));
+
+        // Frames with broken zone bits are ignored
+        schedule.update(&Frame(0), &utc(1, 1));
+        assert_eq!(schedule.zone(&utc(1, 1)), Some(Zone::Cet));
+    }
+
+    #[test]
+    fn no_second_change_after_the_switch() {
+        // Reception starts with the frame of the full hour in the new zone,
+        // then it is lost
+        let mut schedule = ZoneSchedule::new();
+        schedule.update(&Frame(1 << 16 | 1 << 18), &utc(1, 0));
+        assert_eq!(schedule.zone(&utc(1, 59)), Some(Zone::Cet));
+        assert_eq!(schedule.zone(&utc(2, 0)), Some(Zone::Cet));
+    }
+}
@@ -0,0 +1,225 @@
+//! Statistics of the reception, e.g. for aligning the antenna
+//!
+//! The figures of a minute are kept until the next minute gap, the quality
+//! follows every pulse so it reacts within a few seconds.
+
+use super::pulse::Pulse;
+use super::Frame;
+use crate::timebase::{Duration, Instant};
+
+/// Width of a bin of the histogram
+const BIN: Duration = Duration::from_millis(20);
+/// Bins of the histogram, the last one takes all longer pulses
+pub const BINS: usize = 16;
+/// Without a pulse for this long there is no signal
+const SIGNAL_TIMEOUT: Duration = Duration::from_millis(3000);
+/// Highest quality in bars
+pub const MAX_BARS: u8 = 4;
+
+/// Pulses of a minute
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Minute {
+    /// Pulses by width in 20 ms steps
+    pub histogram: [u8; BINS],
+    pub pulses: u8,
+    /// Pulses with a width or distance out of tolerance
+    pub invalid: u8,
+    /// Edges rejected as noise
+    pub noise: u16,
+}
+
+impl Minute {
+    /// Invalid pulses per thousand, `None` without pulses
+    pub fn bit_error_rate(&self) -> Option<u16> {
+        match self.pulses {
+            0 => None,
+            pulses => Some(u16::from(self.invalid) * 1000 / u16::from(pulses)),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Stats {
+    current: Minute,
+    last: Minute,
+    frames: u16,
+    failed: u16,
+    last_pulse: Option<Instant>,
+    last_sync: Option<Instant>,
+    call_bit: bool,
+    /// Share of good pulses in percent, errors and noise lower it quickly
+    score: u8,
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counts a classified pulse and its width
+    // `div_ceil` is newer than the AVR toolchain
+    #[allow(clippy::manual_div_ceil)]
+    pub fn pulse(&mut self, pulse: Pulse, width: Option<Duration>, at: Instant) {
+        if let Some(width) = width {
+            let bin = (width.as_millis() / BIN.as_millis()) as usize;
+            let count = &mut self.current.histogram[bin.min(BINS - 1)];
+            *count = count.saturating_add(1);
+        }
+        self.current.pulses = self.current.pulses.saturating_add(1);
+        if pulse == Pulse::Invalid {
+            self.current.invalid = self.current.invalid.saturating_add(1);
+            self.score -= self.score / 2;
+        } else {
+            self.score += (100 - self.score + 3) / 4;
+        }
+        self.last_pulse = Some(at);
+    }
+
+    /// Counts edges rejected as noise
+    pub fn noise(&mut self, edges: u16) {
+        self.current.noise = self.current.noise.saturating_add(edges);
+        for _ in 0..edges.min(8) {
+            self.score -= self.score / 8;
+        }
+    }
+
+    /// Ends the minute at the minute gap with the frame if it had all bits
+    pub fn minute(&mut self, frame: Option<&Frame>) {
+        if let Some(frame) = frame {
+            self.frames = self.frames.saturating_add(1);
+            self.call_bit = frame.call_bit();
+        } else {
+            self.failed = self.failed.saturating_add(1);
+        }
+        self.last = self.current;
+        self.current = Minute::default();
+    }
+
+    /// A complete frame could not be decoded, it counts as failed and
+    /// lowers the quality like an invalid pulse
+    pub fn rejected(&mut self) {
+        self.frames = self.frames.saturating_sub(1);
+        self.failed = self.failed.saturating_add(1);
+        self.score -= self.score / 2;
+    }
+
+    /// A frame was confirmed as the time
+    pub fn synced(&mut self, at: Instant) {
+        self.last_sync = Some(at);
+    }
+
+    /// Pulses of the last complete minute
+    pub fn last_minute(&self) -> &Minute {
+        &self.last
+    }
+
+    /// Complete frames since the start, without the rejected ones
+    pub fn frames(&self) -> u16 {
+        self.frames
+    }
+
+    /// Incomplete or rejected frames since the start
+    pub fn failed(&self) -> u16 {
+        self.failed
+    }
+
+    /// Call bit of the last complete frame, see `Frame::call_bit`
+    pub fn call_bit(&self) -> bool {
+        self.call_bit
+    }
+
+    pub fn since_sync(&self, now: Instant) -> Option<Duration> {
+        self.last_sync.map(|sync| now - sync)
+    }
+
+    /// Quality of the signal from 0 to `MAX_BARS`
+    pub fn bars(&self, now: Instant) -> u8 {
+        match self.last_pulse {
+            Some(pulse) if now - pulse < SIGNAL_TIMEOUT => (self.score / 25).min(MAX_BARS),
+            _ => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(millis: u32) -> Instant {
+        Instant::from_millis(millis)
+    }
+
+    fn ms(millis: u32) -> Option<Duration> {
+        Some(Duration::from_millis(millis))
+    }
+
+    #[test]
+    fn histogram_of_the_last_minute() {
+        let mut stats = Stats::new();
+        stats.pulse(Pulse::Bit(false), ms(95), at(0));
+        stats.pulse(Pulse::Bit(true), ms(205), at(1000));
+        stats.pulse(Pulse::Invalid, ms(900), at(2000));
+        stats.pulse(Pulse::Invalid, None, at(3000));
+        stats.noise(3);
+        assert_eq!(stats.last_minute(), &Minute::default());
+
+        stats.minute(None);
+        let minute = stats.last_minute();
+        assert_eq!(minute.histogram[4], 1);
+        assert_eq!(minute.histogram[10], 1);
+        assert_eq!(minute.histogram[BINS - 1], 1);
+        assert_eq!(minute.pulses, 4);
+        assert_eq!(minute.invalid, 2);
+        assert_eq!(minute.noise, 3);
+        assert_eq!(minute.bit_error_rate(), Some(500));
+        assert_eq!((stats.frames(), stats.failed()), (0, 1));
+
+        stats.minute(Some(&Frame(1 << 15)));
+        assert_eq!(stats.last_minute().bit_error_rate(), None);
+        assert_eq!((stats.frames(), stats.failed()), (1, 1));
+        assert!(stats.call_bit());
+    }
+
+    #[test]
+    fn bars_follow_the_pulses() {
+        let mut stats = Stats::new();
+        assert_eq!(stats.bars(at(0)), 0);
+        for second in 0..20 {
+            stats.pulse(Pulse::Bit(false), ms(100), at(second * 1000));
+        }
+        assert_eq!(stats.bars(at(20_000)), MAX_BARS);
+
+        stats.pulse(Pulse::Invalid, ms(50), at(20_000));
+        assert_eq!(stats.bars(at(20_000)), 2);
+        stats.noise(5);
+        assert_eq!(stats.bars(at(20_000)), 1);
+
+        // The signal is gone
+        assert_eq!(stats.bars(at(23_000)), 0);
+    }
+
+    #[test]
+    fn rejected_frames_count_as_failed() {
+        let mut stats = Stats::new();
+        for second in 0..20 {
+            stats.pulse(Pulse::Bit(false), ms(100), at(second * 1000));
+        }
+        stats.minute(Some(&Frame(0)));
+        assert_eq!((stats.frames(), stats.failed()), (1, 0));
+
+        stats.rejected();
+        assert_eq!((stats.frames(), stats.failed()), (0, 1));
+        assert_eq!(stats.bars(at(20_000)), 2);
+    }
+
+    #[test]
+    fn time_since_sync() {
+        let mut stats = Stats::new();
+        assert_eq!(stats.since_sync(at(5000)), None);
+        stats.synced(at(1000));
+        assert_eq!(
+            stats.since_sync(at(5000)),
+            Some(Duration::from_millis(4000))
+        );
+    }
+}
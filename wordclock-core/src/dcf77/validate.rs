@@ -0,0 +1,262 @@
+//! Plausibility checks of the DCF77 frames
+//!
+//! Noise can flip bits without breaking the timing of the pulses, e.g.
+//! during a thunderstorm. `decode` checks everything a single frame allows,
+//! the `Validator` then requires a number of consecutive frames before the
+//! time is used.
+
+use core::time::Duration;
+
+use super::{Frame, Parity};
+use crate::rtc::from_bcd;
+use crate::source::distance;
+use crate::time::{Field, Time, TimeError, Weekday};
+use crate::timezone;
+
+/// Always 0, the start of the minute
+const MINUTE_START: u32 = 0;
+/// Always 1, the start of the time information
+const TIME_START: u32 = 20;
+
+/// Frames needed for a time far off the clock, at least
+const JUMP_FRAMES: u8 = 5;
+
+/// First bit, length and field of the BCD coded values
+const MINUTE: (u32, u32, Field) = (21, 7, Field::Minute);
+const HOUR: (u32, u32, Field) = (29, 6, Field::Hour);
+const DAY: (u32, u32, Field) = (36, 6, Field::Day);
+const WEEKDAY: (u32, u32, Field) = (42, 3, Field::Weekday);
+const MONTH: (u32, u32, Field) = (45, 5, Field::Month);
+const YEAR: (u32, u32, Field) = (50, 8, Field::Year);
+
+/// Bits covered by each parity bit, the parity bit is the last one
+const PARITIES: [(u32, u32, Parity); 3] = [
+    (21, 28, Parity::Minute),
+    (29, 35, Parity::Hour),
+    (36, 58, Parity::Date),
+];
+
+/// UTC time of the frame, or the first check that failed
+pub fn decode(frame: &Frame) -> Result<Time, TimeError> {
+    if frame.bit(MINUTE_START) || !frame.bit(TIME_START) {
+        return Err(TimeError::StartBit);
+    }
+    for &(first, last, parity) in PARITIES.iter() {
+        let ones = (first..=last).filter(|&second| frame.bit(second)).count();
+        if ones % 2 != 0 {
+            return Err(TimeError::Parity(parity));
+        }
+    }
+    let zone = frame.zone().ok_or(TimeError::Zone)?;
+
+    let min = bcd(frame, MINUTE)?;
+    let hour = bcd(frame, HOUR)?;
+    let day = bcd(frame, DAY)?;
+    let weekday = bcd(frame, WEEKDAY)?;
+    let month = bcd(frame, MONTH)?;
+    let year = bcd(frame, YEAR)?;
+    let local = Time::new(2000 + u16::from(year), month, day, hour, min, 0)?;
+    if Weekday::from_number(weekday) != Some(local.weekday()) {
+        return Err(TimeError::InvalidField(Field::Weekday));
+    }
+    timezone::to_utc(&local, Some(zone)).ok_or(TimeError::InvalidField(Field::Year))
+}
+
+fn bcd(frame: &Frame, (first, len, field): (u32, u32, Field)) -> Result<u8, TimeError> {
+    let value = (frame.0 >> first) & ((1 << len) - 1);
+    from_bcd(value as u8, field)
+}
+
+/// Accepts a time once enough consecutive frames agree on it
+pub struct Validator {
+    required: u8,
+    max_jump: Duration,
+    /// Time of the last frame and the consecutive frames up to it
+    chain: Option<(Time, u8)>,
+}
+
+impl Validator {
+    /// `required` consecutive frames confirm a time, a time more than
+    /// `max_jump` off the clock needs at least `JUMP_FRAMES`
+    pub fn new(required: u8, max_jump: Duration) -> Self {
+        Self {
+            required: required.max(1),
+            max_jump,
+            chain: None,
+        }
+    }
+
+    pub fn set_required(&mut self, required: u8) {
+        self.required = required.max(1);
+    }
+
+    /// Checks the time decoded from a frame against the previous frames and
+    /// the time of the clock, `Ok(None)` while more frames are needed
+    pub fn check(
+        &mut self,
+        decoded: Result<Time, TimeError>,
+        clock: Option<&Time>,
+    ) -> Result<Option<Time>, TimeError> {
+        let time = match decoded {
+            Ok(time) => time,
+            Err(err) => {
+                self.chain = None;
+                return Err(err);
+            }
+        };
+        let count = match self.chain {
+            Some((previous, count)) if previous.inc_minutes() == time => count.saturating_add(1),
+            // Frames were missed, the chain starts again with this one
+            Some((previous, _))
+                if time > previous && distance(&previous, &time) <= self.max_jump =>
+            {
+                1
+            }
+            Some(_) => {
+                self.chain = Some((time, 1));
+                return Err(TimeError::Inconsistent);
+            }
+            None => 1,
+        };
+        self.chain = Some((time, count));
+
+        let jump = match clock {
+            Some(clock) => distance(clock, &time) > self.max_jump,
+            None => false,
+        };
+        if jump && count < self.required.max(JUMP_FRAMES) {
+            Err(TimeError::ImplausibleJump)
+        } else if count < self.required {
+            Ok(None)
+        } else {
+            Ok(Some(time))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: Duration = Duration::from_secs(60);
+
+    /// Frame of 2021-03-01 12:34 CET, a Monday
+    const FRAME: Frame = Frame(
+        1 << 18
+            | 1 << 20
+            | 0x34 << 21
+            | 1 << 28
+            | 0x12 << 29
+            | 0x01 << 36
+            | 1 << 42
+            | 0x03 << 45
+            | 0x21 << 50,
+    );
+
+    fn utc(hour: u8, min: u8) -> Time {
+        Time::new(2021, 3, 1, hour, min, 0).unwrap()
+    }
+
+    #[test]
+    fn decodes_a_frame() {
+        assert_eq!(decode(&FRAME), Ok(utc(11, 34)));
+    }
+
+    #[test]
+    fn reports_the_failed_check() {
+        let flip = |bit: u32| decode(&Frame(FRAME.0 ^ 1 << bit));
+        assert_eq!(flip(0), Err(TimeError::StartBit));
+        assert_eq!(flip(20), Err(TimeError::StartBit));
+        assert_eq!(flip(22), Err(TimeError::Parity(Parity::Minute)));
+        assert_eq!(flip(35), Err(TimeError::Parity(Parity::Hour)));
+        assert_eq!(flip(55), Err(TimeError::Parity(Parity::Date)));
+        assert_eq!(flip(17), Err(TimeError::Zone));
+
+        // Flipping two bits keeps the parity
+        let flip2 = |a: u32, b: u32| decode(&Frame(FRAME.0 ^ 1 << a ^ 1 << b));
+        assert_eq!(flip2(21, 24), Err(TimeError::InvalidField(Field::Minute)));
+        assert_eq!(flip2(32, 33), Err(TimeError::InvalidField(Field::Hour)));
+        assert_eq!(flip2(42, 43), Err(TimeError::InvalidField(Field::Weekday)));
+        assert_eq!(flip2(38, 39), Err(TimeError::InvalidField(Field::Day)));
+        assert_eq!(flip2(48, 49), Err(TimeError::InvalidField(Field::Month)));
+        // Digits above 9, like the tens of the year 2165 seen after a storm
+        assert_eq!(flip2(53, 51), Err(TimeError::InvalidField(Field::Year)));
+    }
+
+    #[test]
+    fn requires_consecutive_frames() {
+        let mut validator = Validator::new(3, 2 * MINUTE);
+        assert_eq!(validator.check(Ok(utc(11, 34)), None), Ok(None));
+        assert_eq!(validator.check(Ok(utc(11, 35)), None), Ok(None));
+        // A minute was missed
+        assert_eq!(validator.check(Ok(utc(11, 37)), None), Ok(None));
+        assert_eq!(validator.check(Ok(utc(11, 38)), None), Ok(None));
+        assert_eq!(
+            validator.check(Ok(utc(11, 39)), None),
+            Ok(Some(utc(11, 39)))
+        );
+        assert_eq!(
+            validator.check(Ok(utc(11, 40)), None),
+            Ok(Some(utc(11, 40)))
+        );
+
+        // An error breaks the chain
+        assert_eq!(
+            validator.check(Err(TimeError::Incomplete), None),
+            Err(TimeError::Incomplete)
+        );
+        assert_eq!(validator.check(Ok(utc(11, 42)), None), Ok(None));
+    }
+
+    #[test]
+    fn contradicting_frames_are_inconsistent() {
+        let mut validator = Validator::new(1, 2 * MINUTE);
+        assert_eq!(
+            validator.check(Ok(utc(11, 34)), None),
+            Ok(Some(utc(11, 34)))
+        );
+        // After a missed minute the next frame is taken right away
+        assert_eq!(
+            validator.check(Ok(utc(11, 36)), None),
+            Ok(Some(utc(11, 36)))
+        );
+        assert_eq!(
+            validator.check(Ok(utc(11, 36)), None),
+            Err(TimeError::Inconsistent)
+        );
+        assert_eq!(
+            validator.check(Ok(utc(11, 10)), None),
+            Err(TimeError::Inconsistent)
+        );
+        assert_eq!(
+            validator.check(Ok(utc(11, 11)), None),
+            Ok(Some(utc(11, 11)))
+        );
+        assert_eq!(
+            validator.check(Ok(utc(11, 30)), None),
+            Err(TimeError::Inconsistent)
+        );
+    }
+
+    #[test]
+    fn jumps_need_more_frames() {
+        let mut validator = Validator::new(1, 2 * MINUTE);
+        let clock = utc(11, 0);
+        assert_eq!(
+            validator.check(Ok(utc(11, 2)), Some(&clock)),
+            Ok(Some(utc(11, 2)))
+        );
+
+        let mut validator = Validator::new(1, 2 * MINUTE);
+        for min in 10..14 {
+            assert_eq!(
+                validator.check(Ok(utc(11, min)), Some(&clock)),
+                Err(TimeError::ImplausibleJump)
+            );
+        }
+        assert_eq!(
+            validator.check(Ok(utc(11, 14)), Some(&clock)),
+            Ok(Some(utc(11, 14)))
+        );
+    }
+}
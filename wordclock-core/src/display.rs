@@ -0,0 +1,321 @@
+use core::fmt::Debug;
+
+use embedded_hal::blocking::delay::DelayUs;
+use embedded_hal::digital::v2::{OutputPin, StatefulOutputPin};
+
+use crate::language::Language;
+use crate::phrase::{phrase, Phrase, Rounding, Word};
+use crate::time::Time;
+
+pub mod corner;
+
+const MAX_TIME_ON: u16 = 20_000;
+const MIN_TIME_ON: u16 = 1_000;
+
+/// One entry per row of the matrix. The LEDs are active low, a cleared bit
+/// lights the letter.
+pub type DisplayBuffer = [u16; 10];
+
+/// Buffer with all LEDs switched off
+pub const BLANK: DisplayBuffer = [0xffff; 10];
+
+/// Driver for the LED matrix behind the two cascaded 16 bit shift registers
+///
+/// The first 16 bits shifted out are the columns of a row, the following 16
+/// bits select the row.
+pub struct Display<LATCH, CLK, DATA, OE, DELAY> {
+    pin_latch: LATCH,
+    pin_clock: CLK,
+    pin_data: DATA,
+    pin_output_enable: OE,
+    delay: DELAY,
+
+    pub data: DisplayBuffer,
+
+    brightness: u8,
+}
+
+impl<LATCH, CLK, DATA, OE, DELAY, E> Display<LATCH, CLK, DATA, OE, DELAY>
+where
+    LATCH: OutputPin<Error = E>,
+    CLK: OutputPin<Error = E>,
+    DATA: OutputPin<Error = E>,
+    OE: OutputPin<Error = E> + StatefulOutputPin,
+    DELAY: DelayUs<u16>,
+    E: Debug,
+{
+    pub fn new(
+        pin_latch: LATCH,
+        pin_clock: CLK,
+        pin_data: DATA,
+        pin_output_enable: OE,
+        delay: DELAY,
+    ) -> Self {
+        Self {
+            pin_latch,
+            pin_clock,
+            pin_data,
+            pin_output_enable,
+            delay,
+            data: [0b0000_0000_0000_0000; 10],
+            brightness: 255,
+        }
+    }
+
+    /// Enables the LED Output
+    pub fn enable_output(&mut self) {
+        // Set pin to low to enable output
+        self.pin_output_enable.set_low().unwrap();
+    }
+
+    /// Disables the LED Output
+    pub fn disable_output(&mut self) {
+        // Set pin to hight to disable output
+        self.pin_output_enable.set_high().unwrap();
+    }
+
+    /// Query the current Ouptut status
+    pub fn enabled(&self) -> bool {
+        self.pin_output_enable.is_set_low().unwrap()
+    }
+
+    /// Query the current brightness
+    pub fn brightness(&self) -> u8 {
+        self.brightness
+    }
+
+    /// Sets the brightness
+    pub fn set_brightness(&mut self, brightness: u8) {
+        self.brightness = brightness;
+    }
+
+    /// Shifts a row of data to the shift registers and loads them to the outputs
+    fn display_line(&mut self, line: usize) {
+        // Set latch to low
+        self.pin_latch.set_low().unwrap();
+        // Output data for the current line to shifregister
+        let mut mask = 0x0001;
+        for _ in 0..16 {
+            self.pin_clock.set_low().unwrap();
+            if (self.data[line] & mask) != 0 {
+                self.pin_data.set_high().unwrap();
+            } else {
+                self.pin_data.set_low().unwrap();
+            }
+            self.pin_clock.set_high().unwrap();
+            mask <<= 1;
+        }
+        // select the correct line
+        for line_bit in 0..16 {
+            self.pin_clock.set_low().unwrap();
+            if line == line_bit {
+                self.pin_data.set_high().unwrap();
+            } else {
+                self.pin_data.set_low().unwrap();
+            }
+            self.pin_clock.set_high().unwrap();
+        }
+        // Store shiftregister in store register
+        self.pin_latch.set_high().unwrap();
+    }
+
+    /// Update the display with the buffer data
+    pub fn update_display(&mut self) {
+        // Calculate time for a line to be on
+        let time_on: u16 =
+            MIN_TIME_ON.saturating_add((MAX_TIME_ON - MIN_TIME_ON) / self.brightness.max(1) as u16);
+
+        // Output a line
+        for line in 0..self.data.len() {
+            if self.data[line] != 0xffff {
+                // Skip Blank lines
+                self.display_line(line);
+                self.delay.delay_us(time_on);
+            }
+        }
+    }
+
+    /// Clear the display
+    pub fn clear(&mut self) {
+        self.data = BLANK;
+    }
+
+    /// Update the data with the words of the language for a datetime
+    pub fn update_data<L: Language + ?Sized>(&mut self, language: &L, time: &Time) {
+        update_data(&mut self.data, language, time);
+    }
+
+    /// Update the data with a phrase
+    pub fn show(&mut self, phrase: &Phrase) {
+        self.data = render(phrase);
+    }
+
+    /// Shows that no source provided the time yet
+    pub fn show_not_set(&mut self) {
+        self.data = render_not_set();
+    }
+
+    pub fn set_word(&mut self, word: &Word) {
+        set_word(&mut self.data, word);
+    }
+
+    pub fn set_line(&mut self, line: (usize, u16)) {
+        set_line(&mut self.data, line);
+    }
+}
+
+/// Update the data with the words of the language for a datetime, the
+/// minutes since the last five minute step are shown in the corners
+pub fn update_data<L: Language + ?Sized>(data: &mut DisplayBuffer, language: &L, time: &Time) {
+    *data = render(&phrase(language, time, Rounding::Floor, true));
+}
+
+/// Turns a phrase into the LEDs to light
+pub fn render(phrase: &Phrase) -> DisplayBuffer {
+    let mut data = BLANK;
+    for word in phrase.words() {
+        set_word(&mut data, word);
+    }
+    set_corners(&mut data, phrase.corners);
+    data
+}
+
+/// Corner LEDs in the order they count the minutes after a phrase
+const CORNERS: [(usize, u16); 4] = [
+    corner::TOP_LEFT,
+    corner::TOP_RIGHT,
+    corner::BOTTOM_LEFT,
+    corner::BOTTOM_RIGHT,
+];
+
+/// All four corners without a word, a real time always shows words
+pub fn render_not_set() -> DisplayBuffer {
+    let mut data = BLANK;
+    set_corners(&mut data, 4);
+    data
+}
+
+fn set_corners(data: &mut DisplayBuffer, corners: i8) {
+    // Minutes before the phrase count backwards from the bottom right
+    let count = corners.unsigned_abs().min(4) as usize;
+    if corners > 0 {
+        CORNERS[..count]
+            .iter()
+            .for_each(|corner| set_line(data, *corner));
+    } else {
+        CORNERS
+            .iter()
+            .rev()
+            .take(count)
+            .for_each(|corner| set_line(data, *corner));
+    }
+}
+
+/// Toggles the LEDs of a word in the buffer
+pub fn set_word(data: &mut DisplayBuffer, word: &Word) {
+    set_line(data, (word.row, word.mask));
+}
+
+/// Toggles the LEDs of a word or corner in the buffer
+pub fn set_line(data: &mut DisplayBuffer, line: (usize, u16)) {
+    data[line.0] ^= line.1;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::language::german::{words, German};
+
+    fn buffer(words: &[Word], corners: &[(usize, u16)]) -> DisplayBuffer {
+        let mut data = BLANK;
+        for word in words {
+            set_word(&mut data, word);
+        }
+        for corner in corners {
+            set_line(&mut data, *corner);
+        }
+        data
+    }
+
+    #[test]
+    fn full_hour() {
+        let mut data = BLANK;
+        update_data(
+            &mut data,
+            &German::default(),
+            &Time::new(2021, 1, 1, 3, 0, 0).unwrap(),
+        );
+        assert_eq!(
+            data,
+            buffer(&[words::ES, words::IST, words::DREI_HOUR, words::UHR], &[])
+        );
+    }
+
+    #[test]
+    fn quarter_to_with_corners() {
+        let mut data = BLANK;
+        update_data(
+            &mut data,
+            &German::default(),
+            &Time::new(2021, 1, 1, 7, 47, 0).unwrap(),
+        );
+        assert_eq!(
+            data,
+            buffer(
+                &[
+                    words::ES,
+                    words::IST,
+                    words::VIERTEL,
+                    words::VOR,
+                    words::ACHT_HOUR,
+                ],
+                &[corner::TOP_LEFT, corner::TOP_RIGHT]
+            )
+        );
+    }
+
+    #[test]
+    fn render_minutes_before_phrase() {
+        let mut phrase = Phrase::new();
+        phrase.corners = -2;
+        assert_eq!(
+            render(&phrase),
+            buffer(&[], &[corner::BOTTOM_RIGHT, corner::BOTTOM_LEFT])
+        );
+    }
+
+    #[test]
+    fn render_all_corners() {
+        let mut phrase = Phrase::new();
+        phrase.push(&words::ES);
+        phrase.corners = 4;
+        assert_eq!(
+            render(&phrase),
+            buffer(
+                &[words::ES],
+                &[
+                    corner::TOP_LEFT,
+                    corner::TOP_RIGHT,
+                    corner::BOTTOM_LEFT,
+                    corner::BOTTOM_RIGHT,
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn render_time_not_set() {
+        assert_eq!(
+            render_not_set(),
+            buffer(
+                &[],
+                &[
+                    corner::TOP_LEFT,
+                    corner::TOP_RIGHT,
+                    corner::BOTTOM_LEFT,
+                    corner::BOTTOM_RIGHT,
+                ]
+            )
+        );
+    }
+}
@@ -0,0 +1,257 @@
+//! Drift of the RTC measured against DCF77
+//!
+//! At each DCF77 sync the offset the RTC gathered since it was last set gives
+//! its drift. The estimate corrects the time read from the RTC until the next
+//! sync, so clocks with poor reception stay accurate for longer. The estimate
+//! and the base it is measured from are kept over a reset.
+
+use crate::time::Time;
+
+/// The RTC is only set again once it is off by this many seconds, shorter
+/// intervals between the syncs cannot be measured with whole seconds
+const MAX_OFFSET: i64 = 2;
+/// Shortest interval between two syncs that gives a measurement
+const MIN_INTERVAL: i64 = 6 * 60 * 60;
+/// Larger estimates are no drift but a broken RTC
+const MAX_TENTH_PPM: i64 = 2000;
+
+/// Time the RTC was set to last
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Base {
+    pub time: Time,
+    /// The time came from a reference and not from the user
+    pub reference: bool,
+}
+
+pub struct Drift {
+    /// Positive if the RTC runs fast
+    tenth_ppm: i16,
+    base: Option<Base>,
+}
+
+impl Drift {
+    pub fn new(tenth_ppm: i16) -> Self {
+        Self::restore(tenth_ppm, None)
+    }
+
+    /// Drift as stored before a reset, corrects the RTC without a new sync
+    pub fn restore(tenth_ppm: i16, base: Option<Base>) -> Self {
+        Self { tenth_ppm, base }
+    }
+
+    /// Base of the correction and the measurement, to be stored with the
+    /// estimate
+    pub fn base(&self) -> Option<Base> {
+        self.base
+    }
+
+    /// Estimated drift in tenths of ppm
+    pub fn tenth_ppm(&self) -> i16 {
+        self.tenth_ppm
+    }
+
+    /// The RTC was set to the time without a reference, e.g. by the user
+    pub fn set(&mut self, time: &Time) {
+        self.base = Some(Base {
+            time: *time,
+            reference: false,
+        });
+    }
+
+    /// Drops the estimate and measures from the reference on, e.g. after
+    /// the estimate was moved to the RTC itself
+    pub fn rebase(&mut self, reference: &Time) {
+        self.tenth_ppm = 0;
+        self.base = Some(Base {
+            time: *reference,
+            reference: true,
+        });
+    }
+
+    /// Compares the RTC with the reference, returns true if the RTC has to
+    /// be set to the reference
+    ///
+    /// `rtc` is the time read from the RTC without correction.
+    pub fn sync(&mut self, rtc: &Time, reference: &Time) -> bool {
+        let offset = seconds_between(reference, rtc);
+        let base = match self.base {
+            Some(base) if base.reference => base.time,
+            _ => {
+                // Measure from this reference on
+                self.base = Some(Base {
+                    time: *reference,
+                    reference: true,
+                });
+                return offset != 0;
+            }
+        };
+        if offset.abs() < MAX_OFFSET {
+            return false;
+        }
+
+        let interval = seconds_between(&base, reference);
+        if interval >= MIN_INTERVAL {
+            let measured = offset * 10_000_000 / interval;
+            if measured.abs() <= MAX_TENTH_PPM {
+                // 0 is also the estimate before the first measurement
+                let estimate = match self.tenth_ppm {
+                    0 => measured,
+                    current => (i64::from(current) + measured) / 2,
+                };
+                self.tenth_ppm = estimate as i16;
+            }
+        }
+        self.base = Some(Base {
+            time: *reference,
+            reference: true,
+        });
+        true
+    }
+
+    /// Removes the drift gathered since the RTC was set from its time
+    pub fn correct(&self, rtc: &Time) -> Time {
+        let base = match self.base {
+            Some(base) => base.time,
+            None => return *rtc,
+        };
+        let elapsed = seconds_between(&base, rtc);
+        let correction = elapsed * i64::from(self.tenth_ppm) / 10_000_000;
+        let corrected = i64::from(rtc.timestamp()) - correction;
+        if corrected < 0 || corrected > i64::from(u32::MAX) {
+            return *rtc;
+        }
+        Time::from_timestamp(corrected as u32).unwrap_or(*rtc)
+    }
+}
+
+/// Seconds from `from` to `to`, negative if `to` is earlier
+fn seconds_between(from: &Time, to: &Time) -> i64 {
+    i64::from(to.timestamp()) - i64::from(from.timestamp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::time::Duration;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    fn start() -> Time {
+        Time::new(2021, 3, 1, 12, 0, 0).unwrap()
+    }
+
+    fn secs(seconds: u64) -> Duration {
+        Duration::from_secs(seconds)
+    }
+
+    #[test]
+    fn first_sync_sets_the_rtc_if_it_is_off() {
+        let mut drift = Drift::new(0);
+        assert!(!drift.sync(&start(), &start()));
+        let mut drift = Drift::new(0);
+        assert!(drift.sync(&(start() + secs(1)), &start()));
+    }
+
+    #[test]
+    fn small_offsets_are_left_alone() {
+        let mut drift = Drift::new(0);
+        drift.sync(&start(), &start());
+        let reference = start() + DAY;
+        assert!(!drift.sync(&(reference + secs(1)), &reference));
+        assert_eq!(drift.tenth_ppm(), 0);
+    }
+
+    #[test]
+    fn measures_the_drift_over_days() {
+        let mut drift = Drift::new(0);
+        drift.sync(&start(), &start());
+
+        // 4 s fast after two days are 23.1 ppm
+        let reference = start() + 2 * DAY;
+        assert!(drift.sync(&(reference + secs(4)), &reference));
+        assert_eq!(drift.tenth_ppm(), 231);
+
+        // 2 s slow after one day are -23.1 ppm, averaged with the estimate
+        let next = reference + DAY;
+        assert!(drift.sync(&(next - secs(2)), &next));
+        assert_eq!(drift.tenth_ppm(), 0);
+    }
+
+    #[test]
+    fn short_intervals_are_no_measurement() {
+        let mut drift = Drift::new(0);
+        drift.sync(&start(), &start());
+        let reference = start() + secs(60 * 60);
+        assert!(drift.sync(&(reference + secs(3)), &reference));
+        assert_eq!(drift.tenth_ppm(), 0);
+    }
+
+    #[test]
+    fn ignores_a_broken_rtc() {
+        let mut drift = Drift::new(50);
+        drift.sync(&start(), &start());
+        let reference = start() + DAY;
+        assert!(drift.sync(&(reference + secs(600)), &reference));
+        assert_eq!(drift.tenth_ppm(), 50);
+    }
+
+    #[test]
+    fn time_set_by_the_user_is_no_reference() {
+        let mut drift = Drift::new(0);
+        drift.set(&start());
+        let reference = start() + 2 * DAY;
+        assert!(drift.sync(&(reference + secs(4)), &reference));
+        assert_eq!(drift.tenth_ppm(), 0);
+    }
+
+    #[test]
+    fn corrects_the_time_since_the_rtc_was_set() {
+        let mut drift = Drift::new(250);
+        assert_eq!(drift.correct(&start()), start());
+
+        drift.sync(&start(), &start());
+        // 25 ppm are 4.32 s in two days
+        let rtc = start() + 2 * DAY;
+        assert_eq!(drift.correct(&rtc), rtc - secs(4));
+
+        let mut drift = Drift::new(-250);
+        drift.set(&start());
+        assert_eq!(drift.correct(&rtc), rtc + secs(4));
+    }
+
+    #[test]
+    fn restored_drift_corrects_without_a_sync() {
+        let mut drift = Drift::new(250);
+        drift.sync(&start(), &start());
+        let drift = Drift::restore(drift.tenth_ppm(), drift.base());
+        let rtc = start() + 2 * DAY;
+        assert_eq!(drift.correct(&rtc), rtc - secs(4));
+
+        // The measurement goes on from the stored base
+        let mut drift = Drift::restore(0, drift.base());
+        let reference = start() + 2 * DAY;
+        assert!(drift.sync(&(reference + secs(4)), &reference));
+        assert_eq!(drift.tenth_ppm(), 231);
+    }
+
+    #[test]
+    fn rebase_keeps_correcting_from_the_reference() {
+        let mut drift = Drift::new(250);
+        drift.sync(&start(), &start());
+        let reference = start() + DAY;
+        drift.rebase(&reference);
+        assert_eq!(drift.tenth_ppm(), 0);
+        assert_eq!(
+            drift.base(),
+            Some(Base {
+                time: reference,
+                reference: true,
+            })
+        );
+
+        // The next sync measures from the rebased reference
+        let next = reference + 2 * DAY;
+        assert!(drift.sync(&(next + secs(4)), &next));
+        assert_eq!(drift.tenth_ppm(), 231);
+    }
+}
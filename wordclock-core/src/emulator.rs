@@ -0,0 +1,6 @@
+//! Emulated hardware for running the clock logic on the host
+//!
+//! Only available with the `emulator` feature, which pulls in `std`.
+
+pub mod ds1307;
+pub mod shift_register;
@@ -0,0 +1,133 @@
+//! Emulation of the DS1307 real time clock behind the I2C bus
+//!
+//! The registers 0x00 to 0x06 hold the time in BCD, 0x07 is the control
+//! register and 0x08 to 0x3f are the 56 bytes of battery backed RAM. Like on
+//! the chip the register pointer wraps from the end of the RAM to 0x00. The
+//! time does not advance on its own, tests set the registers as needed.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use embedded_hal::blocking::i2c::{Write, WriteRead};
+
+use crate::rtc::ADDRESS;
+
+pub const CONTROL: u8 = 0x07;
+pub const RAM_START: u8 = 0x08;
+pub const REGISTER_COUNT: usize = 64;
+
+/// Clock halt bit in the seconds register, set while the oscillator stops
+pub const CLOCK_HALT: u8 = 0b1000_0000;
+/// Square wave enable bit in the control register
+pub const SQWE: u8 = 0b0001_0000;
+
+/// The chip did not acknowledge a transfer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Nack;
+
+struct State {
+    registers: [u8; REGISTER_COUNT],
+    pointer: u8,
+    present: bool,
+}
+
+impl Default for State {
+    /// Registers after the first power up, the oscillator is halted at
+    /// 2000-01-01 00:00:00
+    fn default() -> Self {
+        let mut registers = [0; REGISTER_COUNT];
+        registers[..8].copy_from_slice(&[CLOCK_HALT, 0x00, 0x00, 0x01, 0x01, 0x01, 0x00, 0x03]);
+        Self {
+            registers,
+            pointer: 0,
+            present: true,
+        }
+    }
+}
+
+impl State {
+    fn next(&mut self) -> usize {
+        let register = usize::from(self.pointer);
+        self.pointer = (self.pointer + 1) % REGISTER_COUNT as u8;
+        register
+    }
+}
+
+/// Emulated DS1307, the clones share the registers
+#[derive(Default, Clone)]
+pub struct Ds1307Chip {
+    state: Rc<RefCell<State>>,
+}
+
+impl Ds1307Chip {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&self, register: u8) -> u8 {
+        self.state.borrow().registers[usize::from(register)]
+    }
+
+    pub fn set_register(&self, register: u8, value: u8) {
+        self.state.borrow_mut().registers[usize::from(register)] = value;
+    }
+
+    /// Seconds to year registers
+    pub fn time_registers(&self) -> [u8; 7] {
+        let mut registers = [0; 7];
+        registers.copy_from_slice(&self.state.borrow().registers[..7]);
+        registers
+    }
+
+    pub fn set_time_registers(&self, registers: [u8; 7]) {
+        self.state.borrow_mut().registers[..7].copy_from_slice(&registers);
+    }
+
+    pub fn halted(&self) -> bool {
+        self.register(0) & CLOCK_HALT != 0
+    }
+
+    pub fn square_wave_enabled(&self) -> bool {
+        self.register(CONTROL) & SQWE != 0
+    }
+
+    /// A missing chip does not acknowledge any transfer
+    pub fn set_present(&self, present: bool) {
+        self.state.borrow_mut().present = present;
+    }
+}
+
+impl Write for Ds1307Chip {
+    type Error = Nack;
+
+    /// The first byte sets the register pointer, the following bytes are
+    /// written to consecutive registers
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Nack> {
+        let mut state = self.state.borrow_mut();
+        if !state.present || address != ADDRESS {
+            return Err(Nack);
+        }
+        if let Some((pointer, data)) = bytes.split_first() {
+            state.pointer = pointer % REGISTER_COUNT as u8;
+            for byte in data {
+                let register = state.next();
+                state.registers[register] = *byte;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl WriteRead for Ds1307Chip {
+    type Error = Nack;
+
+    fn write_read(&mut self, address: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), Nack> {
+        self.write(address, bytes)?;
+        let mut state = self.state.borrow_mut();
+        for byte in buffer.iter_mut() {
+            let register = state.next();
+            *byte = state.registers[register];
+        }
+        Ok(())
+    }
+}
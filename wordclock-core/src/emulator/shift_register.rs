@@ -0,0 +1,211 @@
+//! Emulation of the two cascaded shift registers driving the LED matrix
+//!
+//! The pins handed out by [`ShiftRegister`] share one state. Every rising
+//! edge of the clock shifts in the level of the data pin, every rising edge
+//! of the latch stores the 32 shifted bits as a [`Latch`].
+
+use std::cell::RefCell;
+use std::convert::Infallible;
+use std::rc::Rc;
+use std::vec::Vec;
+
+use embedded_hal::blocking::delay::DelayUs;
+use embedded_hal::digital::v2::{OutputPin, StatefulOutputPin};
+
+use crate::display::Display;
+
+/// Display driven by the emulated shift registers
+pub type EmulatedDisplay = Display<LatchPin, ClockPin, DataPin, OutputEnablePin, NoDelay>;
+
+/// Contents of the shift registers at a rising edge of the latch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Latch {
+    /// Data bits in the order they were clocked in
+    pub bits: Vec<bool>,
+}
+
+impl Latch {
+    /// The 16 column bits, the first bit clocked in is bit 0
+    pub fn columns(&self) -> u16 {
+        Self::word(self.bits.get(0..16))
+    }
+
+    /// The 16 row select bits, the first bit clocked in after the columns
+    /// is bit 0
+    pub fn rows(&self) -> u16 {
+        Self::word(self.bits.get(16..32))
+    }
+
+    /// The selected row if exactly one row select bit is set
+    pub fn selected_row(&self) -> Option<usize> {
+        let rows = self.rows();
+        if rows.count_ones() == 1 {
+            Some(rows.trailing_zeros() as usize)
+        } else {
+            None
+        }
+    }
+
+    fn word(bits: Option<&[bool]>) -> u16 {
+        bits.unwrap_or(&[])
+            .iter()
+            .enumerate()
+            .filter(|(_, bit)| **bit)
+            .fold(0, |word, (index, _)| word | 1 << index)
+    }
+}
+
+#[derive(Default)]
+struct State {
+    latch: bool,
+    clock: bool,
+    data: bool,
+    output_enable: bool,
+    shifted: Vec<bool>,
+    latched: Vec<Latch>,
+}
+
+/// Emulated pair of shift registers
+#[derive(Default, Clone)]
+pub struct ShiftRegister {
+    state: Rc<RefCell<State>>,
+}
+
+impl ShiftRegister {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a display driving this shift register
+    pub fn display(&self) -> EmulatedDisplay {
+        Display::new(
+            self.latch_pin(),
+            self.clock_pin(),
+            self.data_pin(),
+            self.output_enable_pin(),
+            NoDelay,
+        )
+    }
+
+    pub fn latch_pin(&self) -> LatchPin {
+        LatchPin(self.state.clone())
+    }
+
+    pub fn clock_pin(&self) -> ClockPin {
+        ClockPin(self.state.clone())
+    }
+
+    pub fn data_pin(&self) -> DataPin {
+        DataPin(self.state.clone())
+    }
+
+    pub fn output_enable_pin(&self) -> OutputEnablePin {
+        OutputEnablePin(self.state.clone())
+    }
+
+    /// Takes all latches recorded since the last call
+    pub fn take_latched(&self) -> Vec<Latch> {
+        std::mem::take(&mut self.state.borrow_mut().latched)
+    }
+
+    /// Bits clocked in since the last latch
+    pub fn pending_bits(&self) -> usize {
+        self.state.borrow().shifted.len()
+    }
+
+    /// Level of the output enable pin, the outputs are active when low
+    pub fn output_enabled(&self) -> bool {
+        !self.state.borrow().output_enable
+    }
+}
+
+pub struct LatchPin(Rc<RefCell<State>>);
+
+impl OutputPin for LatchPin {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.0.borrow_mut().latch = false;
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        let mut state = self.0.borrow_mut();
+        if !state.latch {
+            let bits = std::mem::take(&mut state.shifted);
+            state.latched.push(Latch { bits });
+        }
+        state.latch = true;
+        Ok(())
+    }
+}
+
+pub struct ClockPin(Rc<RefCell<State>>);
+
+impl OutputPin for ClockPin {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.0.borrow_mut().clock = false;
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        let mut state = self.0.borrow_mut();
+        if !state.clock {
+            let data = state.data;
+            state.shifted.push(data);
+        }
+        state.clock = true;
+        Ok(())
+    }
+}
+
+pub struct DataPin(Rc<RefCell<State>>);
+
+impl OutputPin for DataPin {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.0.borrow_mut().data = false;
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.0.borrow_mut().data = true;
+        Ok(())
+    }
+}
+
+pub struct OutputEnablePin(Rc<RefCell<State>>);
+
+impl OutputPin for OutputEnablePin {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.0.borrow_mut().output_enable = false;
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.0.borrow_mut().output_enable = true;
+        Ok(())
+    }
+}
+
+impl StatefulOutputPin for OutputEnablePin {
+    fn is_set_high(&self) -> Result<bool, Self::Error> {
+        Ok(self.0.borrow().output_enable)
+    }
+
+    fn is_set_low(&self) -> Result<bool, Self::Error> {
+        Ok(!self.0.borrow().output_enable)
+    }
+}
+
+/// Delay that returns immediately
+pub struct NoDelay;
+
+impl DelayUs<u16> for NoDelay {
+    fn delay_us(&mut self, _us: u16) {}
+}
@@ -0,0 +1,26 @@
+//! Phrasing of the time for the different front plates
+//!
+//! Every language owns the words of its plate, generated by `build.rs` from
+//! `plates/<language>.txt`, and picks the words to light for a time.
+
+use crate::phrase::{Phrase, Word};
+use crate::time::Time;
+
+pub mod dutch;
+pub mod english;
+pub mod german;
+
+pub use dutch::Dutch;
+pub use english::English;
+pub use german::German;
+
+pub trait Language {
+    /// Letters of the front plate, row by row
+    fn letters(&self) -> &'static [&'static str; 10];
+
+    /// All words of the front plate with their letters
+    fn all_words(&self) -> &'static [Word];
+
+    /// Words for the time, the corner LEDs are not part of it
+    fn words(&self, time: &Time) -> Phrase;
+}
@@ -0,0 +1,117 @@
+//! "Het is tien voor half drie"
+
+use super::Language;
+use crate::phrase::{Phrase, Word};
+use crate::time::Time;
+
+pub mod words {
+    #![allow(clippy::unusual_byte_groupings)]
+    #![allow(dead_code)]
+
+    use crate::phrase::Word;
+
+    include!(concat!(env!("OUT_DIR"), "/dutch.rs"));
+}
+
+#[derive(Default, Clone, Copy, Debug)]
+pub struct Dutch;
+
+impl Language for Dutch {
+    fn letters(&self) -> &'static [&'static str; 10] {
+        &words::LETTERS
+    }
+
+    fn all_words(&self) -> &'static [Word] {
+        words::ALL
+    }
+
+    fn words(&self, time: &Time) -> Phrase {
+        let mut text = Phrase::new();
+        text.push(&words::HET);
+        text.push(&words::IS);
+        set_minutes(&mut text, time.minutes());
+        set_hours(&mut text, time.hour(), time.minutes());
+        if time.minutes() < 5 {
+            text.push(&words::UUR);
+        }
+        text
+    }
+}
+
+fn set_hours(text: &mut Phrase, hour: u8, min: u8) {
+    // From "tien voor half" on the phrase refers to the next hour
+    let mut hours_display = hour;
+    if min >= 20 {
+        hours_display = hour + 1;
+    }
+    match hours_display % 12 {
+        0 => text.push(&words::TWAALF_HOUR),
+        1 => text.push(&words::EEN_HOUR),
+        2 => text.push(&words::TWEE_HOUR),
+        3 => text.push(&words::DRIE_HOUR),
+        4 => text.push(&words::VIER_HOUR),
+        5 => text.push(&words::VIJF_HOUR),
+        6 => text.push(&words::ZES_HOUR),
+        7 => text.push(&words::ZEVEN_HOUR),
+        8 => text.push(&words::ACHT_HOUR),
+        9 => text.push(&words::NEGEN_HOUR),
+        10 => text.push(&words::TIEN_HOUR),
+        11 => text.push(&words::ELF_HOUR),
+        _ => unreachable!(),
+    }
+}
+
+fn set_minutes(text: &mut Phrase, min: u8) {
+    let minutes_round = min - (min % 5);
+    match minutes_round {
+        0 => (),
+        5 => {
+            text.push(&words::VIJF);
+            text.push(&words::OVER);
+        }
+        10 => {
+            text.push(&words::TIEN);
+            text.push(&words::OVER);
+        }
+        15 => {
+            text.push(&words::KWART);
+            text.push(&words::OVER_KWART);
+        }
+        20 => {
+            text.push(&words::TIEN);
+            text.push(&words::VOOR);
+            text.push(&words::HALF);
+        }
+        25 => {
+            text.push(&words::VIJF);
+            text.push(&words::VOOR);
+            text.push(&words::HALF);
+        }
+        30 => {
+            text.push(&words::HALF);
+        }
+        35 => {
+            text.push(&words::VIJF);
+            text.push(&words::OVER);
+            text.push(&words::HALF);
+        }
+        40 => {
+            text.push(&words::TIEN);
+            text.push(&words::OVER);
+            text.push(&words::HALF);
+        }
+        45 => {
+            text.push(&words::KWART);
+            text.push(&words::VOOR_KWART);
+        }
+        50 => {
+            text.push(&words::TIEN);
+            text.push(&words::VOOR);
+        }
+        55 => {
+            text.push(&words::VIJF);
+            text.push(&words::VOOR);
+        }
+        _ => unreachable!(),
+    }
+}
@@ -0,0 +1,118 @@
+//! "It is twenty five to three"
+
+use super::Language;
+use crate::phrase::{Phrase, Word};
+use crate::time::Time;
+
+pub mod words {
+    #![allow(clippy::unusual_byte_groupings)]
+    #![allow(dead_code)]
+
+    use crate::phrase::Word;
+
+    include!(concat!(env!("OUT_DIR"), "/english.rs"));
+}
+
+#[derive(Default, Clone, Copy, Debug)]
+pub struct English;
+
+impl Language for English {
+    fn letters(&self) -> &'static [&'static str; 10] {
+        &words::LETTERS
+    }
+
+    fn all_words(&self) -> &'static [Word] {
+        words::ALL
+    }
+
+    fn words(&self, time: &Time) -> Phrase {
+        let mut text = Phrase::new();
+        text.push(&words::IT);
+        text.push(&words::IS);
+        set_minutes(&mut text, time.minutes());
+        set_hours(&mut text, time.hour(), time.minutes());
+        if time.minutes() < 5 {
+            text.push(&words::OCLOCK);
+        }
+        text
+    }
+}
+
+fn set_hours(text: &mut Phrase, hour: u8, min: u8) {
+    // From "twenty five to" on the phrase refers to the next hour
+    let mut hours_display = hour;
+    if min >= 35 {
+        hours_display = hour + 1;
+    }
+    match hours_display % 12 {
+        0 => text.push(&words::TWELVE_HOUR),
+        1 => text.push(&words::ONE_HOUR),
+        2 => text.push(&words::TWO_HOUR),
+        3 => text.push(&words::THREE_HOUR),
+        4 => text.push(&words::FOUR_HOUR),
+        5 => text.push(&words::FIVE_HOUR),
+        6 => text.push(&words::SIX_HOUR),
+        7 => text.push(&words::SEVEN_HOUR),
+        8 => text.push(&words::EIGHT_HOUR),
+        9 => text.push(&words::NINE_HOUR),
+        10 => text.push(&words::TEN_HOUR),
+        11 => text.push(&words::ELEVEN_HOUR),
+        _ => unreachable!(),
+    }
+}
+
+fn set_minutes(text: &mut Phrase, min: u8) {
+    let minutes_round = min - (min % 5);
+    match minutes_round {
+        0 => (),
+        5 => {
+            text.push(&words::FIVE);
+            text.push(&words::PAST);
+        }
+        10 => {
+            text.push(&words::TEN);
+            text.push(&words::PAST);
+        }
+        15 => {
+            text.push(&words::A);
+            text.push(&words::QUARTER);
+            text.push(&words::PAST);
+        }
+        20 => {
+            text.push(&words::TWENTY);
+            text.push(&words::PAST);
+        }
+        25 => {
+            text.push(&words::TWENTY);
+            text.push(&words::FIVE);
+            text.push(&words::PAST);
+        }
+        30 => {
+            text.push(&words::HALF);
+            text.push(&words::PAST);
+        }
+        35 => {
+            text.push(&words::TWENTY);
+            text.push(&words::FIVE);
+            text.push(&words::TO);
+        }
+        40 => {
+            text.push(&words::TWENTY);
+            text.push(&words::TO);
+        }
+        45 => {
+            text.push(&words::A);
+            text.push(&words::QUARTER);
+            text.push(&words::TO);
+        }
+        50 => {
+            text.push(&words::TEN);
+            text.push(&words::TO);
+        }
+        55 => {
+            text.push(&words::FIVE);
+            text.push(&words::TO);
+        }
+        _ => unreachable!(),
+    }
+}
@@ -0,0 +1,250 @@
+//! "Es ist fuenf vor halb drei"
+
+use super::Language;
+use crate::phrase::{Phrase, Word};
+use crate::time::Time;
+
+pub mod words {
+    #![allow(clippy::unusual_byte_groupings)]
+    #![allow(dead_code)]
+
+    use crate::phrase::Word;
+
+    include!(concat!(env!("OUT_DIR"), "/german.rs"));
+}
+
+/// How quarter hours are phrased
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Quarter {
+    /// "viertel nach zwei", "viertel vor drei"
+    NachVor,
+    /// "viertel drei", "dreiviertel drei" as in the south and east
+    Dreiviertel,
+}
+
+/// How twenty past and twenty to are phrased
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Twenty {
+    /// "zwanzig nach zwei", "zwanzig vor drei"
+    Zwanzig,
+    /// "zehn vor halb drei", "zehn nach halb drei"
+    ZehnHalb,
+}
+
+/// How one o'clock is phrased
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum One {
+    /// "eins uhr"
+    Eins,
+    /// "ein uhr"
+    Ein,
+}
+
+/// Regional phrasing of the german time
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Dialect {
+    pub quarter: Quarter,
+    pub twenty: Twenty,
+    pub one: One,
+}
+
+impl Default for Dialect {
+    fn default() -> Self {
+        Self {
+            quarter: Quarter::NachVor,
+            twenty: Twenty::Zwanzig,
+            one: One::Eins,
+        }
+    }
+}
+
+impl Dialect {
+    /// Packs the dialect into the lower three bits
+    pub fn to_bits(self) -> u8 {
+        (self.quarter == Quarter::Dreiviertel) as u8
+            | ((self.twenty == Twenty::ZehnHalb) as u8) << 1
+            | ((self.one == One::Ein) as u8) << 2
+    }
+
+    pub fn from_bits(bits: u8) -> Self {
+        Self {
+            quarter: if bits & 0b001 != 0 {
+                Quarter::Dreiviertel
+            } else {
+                Quarter::NachVor
+            },
+            twenty: if bits & 0b010 != 0 {
+                Twenty::ZehnHalb
+            } else {
+                Twenty::Zwanzig
+            },
+            one: if bits & 0b100 != 0 {
+                One::Ein
+            } else {
+                One::Eins
+            },
+        }
+    }
+
+    /// Steps through all combinations, used to switch the dialect with a
+    /// single button
+    pub fn next(self) -> Self {
+        Self::from_bits(self.to_bits().wrapping_add(1) & 0b111)
+    }
+}
+
+#[derive(Default, Clone, Copy, Debug)]
+pub struct German {
+    pub dialect: Dialect,
+}
+
+impl Language for German {
+    fn letters(&self) -> &'static [&'static str; 10] {
+        &words::LETTERS
+    }
+
+    fn all_words(&self) -> &'static [Word] {
+        words::ALL
+    }
+
+    fn words(&self, time: &Time) -> Phrase {
+        let mut text = Phrase::new();
+        let minutes_round = time.minutes() - (time.minutes() % 5);
+        // Set Obvious data
+        text.push(&words::ES);
+        text.push(&words::IST);
+        // Set Minutes Dispaly text
+        let next_hour = self.set_minutes(&mut text, minutes_round);
+        // Set Hour Display text
+        let hour = if next_hour {
+            time.hour() + 1
+        } else {
+            time.hour()
+        };
+        self.set_hours(&mut text, hour, minutes_round == 0);
+        if minutes_round == 0 {
+            text.push(&words::UHR);
+        }
+        text
+    }
+}
+
+impl German {
+    fn set_hours(&self, text: &mut Phrase, hour: u8, full_hour: bool) {
+        match hour % 12 {
+            0 => text.push(&words::ZWOELF_HOUR),
+            1 if full_hour && self.dialect.one == One::Ein => text.push(&words::EIN_HOUR),
+            1 => text.push(&words::EINS_HOUR),
+            2 => text.push(&words::ZWEI_HOUR),
+            3 => text.push(&words::DREI_HOUR),
+            4 => text.push(&words::VIER_HOUR),
+            5 => text.push(&words::FUENF_HOUR),
+            6 => text.push(&words::SECHS_HOUR),
+            7 => text.push(&words::SIEBEN_HOUR),
+            8 => text.push(&words::ACHT_HOUR),
+            9 => text.push(&words::NEUN_HOUR),
+            10 => text.push(&words::ZEHN_HOUR),
+            11 => text.push(&words::ELF_HOUR),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Adds the minute words, returns if the phrase refers to the next hour
+    fn set_minutes(&self, text: &mut Phrase, minutes_round: u8) -> bool {
+        match (minutes_round, self.dialect.quarter, self.dialect.twenty) {
+            (0, _, _) => false,
+            (5, _, _) => {
+                text.push(&words::FUENF);
+                text.push(&words::NACH);
+                false
+            }
+            (10, _, _) => {
+                text.push(&words::ZEHN);
+                text.push(&words::NACH);
+                false
+            }
+            (15, Quarter::NachVor, _) => {
+                text.push(&words::VIERTEL);
+                text.push(&words::NACH);
+                false
+            }
+            (15, Quarter::Dreiviertel, _) => {
+                text.push(&words::VIERTEL);
+                true
+            }
+            (20, _, Twenty::Zwanzig) => {
+                text.push(&words::ZWANZIG);
+                text.push(&words::NACH);
+                false
+            }
+            (20, _, Twenty::ZehnHalb) => {
+                text.push(&words::ZEHN);
+                text.push(&words::VOR);
+                text.push(&words::HALB);
+                true
+            }
+            (25, _, _) => {
+                text.push(&words::FUENF);
+                text.push(&words::VOR);
+                text.push(&words::HALB);
+                true
+            }
+            (30, _, _) => {
+                text.push(&words::HALB);
+                true
+            }
+            (35, _, _) => {
+                text.push(&words::FUENF);
+                text.push(&words::NACH);
+                text.push(&words::HALB);
+                true
+            }
+            (40, _, Twenty::Zwanzig) => {
+                text.push(&words::ZWANZIG);
+                text.push(&words::VOR);
+                true
+            }
+            (40, _, Twenty::ZehnHalb) => {
+                text.push(&words::ZEHN);
+                text.push(&words::NACH);
+                text.push(&words::HALB);
+                true
+            }
+            (45, Quarter::NachVor, _) => {
+                text.push(&words::VIERTEL);
+                text.push(&words::VOR);
+                true
+            }
+            (45, Quarter::Dreiviertel, _) => {
+                text.push(&words::DREIVIERTEL);
+                true
+            }
+            (50, _, _) => {
+                text.push(&words::ZEHN);
+                text.push(&words::VOR);
+                true
+            }
+            (55, _, _) => {
+                text.push(&words::FUENF);
+                text.push(&words::VOR);
+                true
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dialect_bits_round_trip() {
+        let mut dialect = Dialect::default();
+        for _ in 0..8 {
+            assert_eq!(Dialect::from_bits(dialect.to_bits()), dialect);
+            dialect = dialect.next();
+        }
+        assert_eq!(dialect, Dialect::default());
+    }
+}
@@ -0,0 +1,25 @@
+//! Hardware independent logic of the wordclock
+//!
+//! Everything in here builds without the avr toolchain, so the logic that
+//! turns a time into words can be tested on the host with `cargo test`.
+#![cfg_attr(not(test), no_std)]
+
+#[cfg(feature = "emulator")]
+extern crate std;
+
+pub mod command;
+pub mod config;
+pub mod dcf77;
+pub mod display;
+pub mod drift;
+#[cfg(feature = "emulator")]
+pub mod emulator;
+pub mod language;
+pub mod phrase;
+pub mod queue;
+pub mod rtc;
+pub mod seconds;
+pub mod source;
+pub mod time;
+pub mod timebase;
+pub mod timezone;
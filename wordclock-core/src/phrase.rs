@@ -0,0 +1,185 @@
+//! Words shown for a time, independent of how they are lit
+//!
+//! A [`Phrase`] is what the clock says, e.g. "es ist fuenf vor halb drei"
+//! plus the corner LEDs for the minutes in between. The display renders it
+//! into LEDs, the firmware can also log it over the serial interface.
+
+use crate::language::Language;
+use crate::time::Time;
+
+/// Maximum number of words of a phrase
+pub const MAX_WORDS: usize = 8;
+
+/// A word of a front plate
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Word {
+    /// Letters as printed on the plate
+    pub letters: &'static str,
+    pub row: usize,
+    /// Columns of the word, column 0 is the most significant bit
+    pub mask: u16,
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Phrase {
+    words: [Option<&'static Word>; MAX_WORDS],
+    len: usize,
+    /// Minutes after the phrase shown by the corner LEDs, negative for
+    /// minutes before it
+    pub corners: i8,
+}
+
+impl Phrase {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a word, panics if more than `MAX_WORDS` are added
+    pub fn push(&mut self, word: &'static Word) {
+        self.words[self.len] = Some(word);
+        self.len += 1;
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The words in the order they are read
+    pub fn words(&self) -> impl Iterator<Item = &'static Word> + '_ {
+        self.words[..self.len].iter().flatten().copied()
+    }
+}
+
+/// How the time is rounded to the five minute steps of the phrases
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Rounding {
+    /// 14:38 reads "fuenf nach halb drei" and three minutes after it
+    Floor,
+    /// 14:38 reads "zwanzig vor drei" and two minutes before it
+    Nearest,
+}
+
+/// Phrase of the language for a time
+///
+/// The minutes between the five minute steps are shown by the corner LEDs
+/// unless `show_corners` is false.
+pub fn phrase<L: Language + ?Sized>(
+    language: &L,
+    time: &Time,
+    rounding: Rounding,
+    show_corners: bool,
+) -> Phrase {
+    let remainder = time.minutes() % 5;
+    let (reference, corners) = match rounding {
+        Rounding::Nearest if remainder > 2 => {
+            let mut reference = *time;
+            for _ in remainder..5 {
+                reference = reference.inc_minutes();
+            }
+            (reference, remainder as i8 - 5)
+        }
+        _ => (*time, remainder as i8),
+    };
+
+    let mut phrase = language.words(&reference);
+    if show_corners {
+        phrase.corners = corners;
+    }
+    phrase
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::language::german::{words, German};
+
+    #[test]
+    fn words_in_reading_order() {
+        let phrase = phrase(
+            &German::default(),
+            &Time::new(2021, 1, 1, 14, 37, 0).unwrap(),
+            Rounding::Floor,
+            true,
+        );
+        let letters: Vec<&str> = phrase.words().map(|word| word.letters).collect();
+        assert_eq!(letters, ["ES", "IST", "FÜNF", "NACH", "HALB", "DREI"]);
+        assert_eq!(phrase.corners, 2);
+    }
+
+    #[test]
+    fn full_hour_has_no_corners() {
+        let phrase = phrase(
+            &German::default(),
+            &Time::new(2021, 1, 1, 9, 0, 0).unwrap(),
+            Rounding::Floor,
+            true,
+        );
+        assert!(phrase
+            .words()
+            .eq([&words::ES, &words::IST, &words::NEUN_HOUR, &words::UHR]
+                .iter()
+                .copied()));
+        assert_eq!(phrase.corners, 0);
+    }
+
+    #[test]
+    fn nearest_refers_to_the_next_step() {
+        let german = German::default();
+        let phrase = phrase(
+            &german,
+            &Time::new(2021, 1, 1, 14, 38, 0).unwrap(),
+            Rounding::Nearest,
+            true,
+        );
+        let letters: Vec<&str> = phrase.words().map(|word| word.letters).collect();
+        assert_eq!(letters, ["ES", "IST", "ZWANZIG", "VOR", "DREI"]);
+        assert_eq!(phrase.corners, -2);
+
+        let phrase = super::phrase(
+            &german,
+            &Time::new(2021, 1, 1, 23, 58, 0).unwrap(),
+            Rounding::Nearest,
+            true,
+        );
+        let letters: Vec<&str> = phrase.words().map(|word| word.letters).collect();
+        assert_eq!(letters, ["ES", "IST", "ZWÖLF", "UHR"]);
+        assert_eq!(phrase.corners, -2);
+
+        // The last minutes of the supported range
+        let phrase = super::phrase(
+            &german,
+            &Time::new(2099, 12, 31, 23, 58, 0).unwrap(),
+            Rounding::Nearest,
+            true,
+        );
+        assert_eq!(phrase.corners, -2);
+    }
+
+    #[test]
+    fn nearest_keeps_the_lower_step() {
+        let phrase = phrase(
+            &German::default(),
+            &Time::new(2021, 1, 1, 14, 37, 0).unwrap(),
+            Rounding::Nearest,
+            true,
+        );
+        let letters: Vec<&str> = phrase.words().map(|word| word.letters).collect();
+        assert_eq!(letters, ["ES", "IST", "FÜNF", "NACH", "HALB", "DREI"]);
+        assert_eq!(phrase.corners, 2);
+    }
+
+    #[test]
+    fn corners_can_be_disabled() {
+        let phrase = phrase(
+            &German::default(),
+            &Time::new(2021, 1, 1, 14, 38, 0).unwrap(),
+            Rounding::Nearest,
+            false,
+        );
+        assert_eq!(phrase.corners, 0);
+    }
+}
@@ -0,0 +1,256 @@
+//! Queue handing data from an interrupt to the main loop
+//!
+//! One side only pushes and the other only pops, so each index is written by
+//! a single side. That needs no compare and swap, which the atmega328p lacks,
+//! only atomic loads and stores of `u8`.
+
+use core::cell::UnsafeCell;
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use core::ptr;
+use core::sync::atomic::{AtomicU8, Ordering};
+
+/// Single producer single consumer queue of `N` items
+///
+/// The indices count up to 255 and wrap, so `N` has to divide 256, e.g. 8,
+/// 16 or 32. Other capacities fail to build.
+pub struct Queue<T, const N: usize> {
+    buffer: UnsafeCell<MaybeUninit<[T; N]>>,
+    /// Written by the consumer
+    head: AtomicU8,
+    /// Written by the producer
+    tail: AtomicU8,
+    /// Items dropped because the queue was full, wraps
+    overflows: AtomicU8,
+}
+
+unsafe impl<T: Send, const N: usize> Sync for Queue<T, N> {}
+
+impl<T, const N: usize> Queue<T, N> {
+    /// The slot of an index stays the same when it wraps. Indexing past the
+    /// array fails the build for other capacities, `assert!` in a constant is
+    /// newer than the AVR toolchain.
+    const CAPACITY: () = [()][!(N > 0 && N < 256 && 256 % N == 0) as usize];
+
+    pub const fn new() -> Self {
+        let () = Self::CAPACITY;
+        Self {
+            buffer: UnsafeCell::new(MaybeUninit::uninit()),
+            head: AtomicU8::new(0),
+            tail: AtomicU8::new(0),
+            overflows: AtomicU8::new(0),
+        }
+    }
+
+    /// Ends for the two sides, e.g. the interrupt and the main loop
+    pub fn split(&mut self) -> (Producer<'_, T, N>, Consumer<'_, T, N>) {
+        let overflows = self.overflows.load(Ordering::Relaxed);
+        (
+            Producer {
+                queue: self,
+                _not_sync: PhantomData,
+            },
+            Consumer {
+                queue: self,
+                seen_overflows: overflows,
+                _not_sync: PhantomData,
+            },
+        )
+    }
+
+    fn slot(&self, index: u8) -> *mut T {
+        (self.buffer.get() as *mut T).wrapping_add(usize::from(index) % N)
+    }
+
+    fn len(&self) -> u8 {
+        let tail = self.tail.load(Ordering::Acquire);
+        tail.wrapping_sub(self.head.load(Ordering::Acquire))
+    }
+
+    /// Only called by the consumer, or with exclusive access
+    fn dequeue(&self) -> Option<T> {
+        let head = self.head.load(Ordering::Relaxed);
+        if head == self.tail.load(Ordering::Acquire) {
+            return None;
+        }
+        // The producer does not touch the slot until head moves on
+        let item = unsafe { ptr::read(self.slot(head)) };
+        self.head.store(head.wrapping_add(1), Ordering::Release);
+        Some(item)
+    }
+}
+
+impl<T, const N: usize> Default for Queue<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Drop for Queue<T, N> {
+    fn drop(&mut self) {
+        while self.dequeue().is_some() {}
+    }
+}
+
+/// Pushing end of a queue
+pub struct Producer<'a, T, const N: usize> {
+    queue: &'a Queue<T, N>,
+    /// Both ends may move to another context, but not be shared
+    _not_sync: PhantomData<*const ()>,
+}
+
+unsafe impl<T: Send, const N: usize> Send for Producer<'_, T, N> {}
+
+impl<T, const N: usize> Producer<'_, T, N> {
+    /// Adds an item, a full queue counts an overflow and returns the item
+    pub fn push(&mut self, item: T) -> Result<(), T> {
+        let queue = self.queue;
+        let tail = queue.tail.load(Ordering::Relaxed);
+        if usize::from(queue.len()) >= N {
+            let overflows = queue.overflows.load(Ordering::Relaxed);
+            queue
+                .overflows
+                .store(overflows.wrapping_add(1), Ordering::Release);
+            return Err(item);
+        }
+        // The consumer does not read the slot until tail moves on
+        unsafe { ptr::write(queue.slot(tail), item) };
+        queue.tail.store(tail.wrapping_add(1), Ordering::Release);
+        Ok(())
+    }
+}
+
+/// Popping end of a queue
+pub struct Consumer<'a, T, const N: usize> {
+    queue: &'a Queue<T, N>,
+    seen_overflows: u8,
+    _not_sync: PhantomData<*const ()>,
+}
+
+unsafe impl<T: Send, const N: usize> Send for Consumer<'_, T, N> {}
+
+impl<T, const N: usize> Consumer<'_, T, N> {
+    /// Removes the oldest item
+    pub fn pop(&mut self) -> Option<T> {
+        self.queue.dequeue()
+    }
+
+    pub fn len(&self) -> u8 {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Items dropped since the last call because the queue was full
+    pub fn take_overflows(&mut self) -> u8 {
+        let overflows = self.queue.overflows.load(Ordering::Acquire);
+        let new = overflows.wrapping_sub(self.seen_overflows);
+        self.seen_overflows = overflows;
+        new
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_in_first_out() {
+        let mut queue: Queue<u8, 4> = Queue::new();
+        let (mut producer, mut consumer) = queue.split();
+        assert_eq!(consumer.pop(), None);
+        producer.push(1).unwrap();
+        producer.push(2).unwrap();
+        assert_eq!(consumer.len(), 2);
+        assert_eq!(consumer.pop(), Some(1));
+        producer.push(3).unwrap();
+        assert_eq!(consumer.pop(), Some(2));
+        assert_eq!(consumer.pop(), Some(3));
+        assert!(consumer.is_empty());
+    }
+
+    #[test]
+    fn counts_overflows() {
+        let mut queue: Queue<u8, 4> = Queue::new();
+        let (mut producer, mut consumer) = queue.split();
+        for item in 0..6 {
+            let _ = producer.push(item);
+        }
+        assert_eq!(producer.push(6), Err(6));
+        assert_eq!(consumer.take_overflows(), 3);
+        assert_eq!(consumer.take_overflows(), 0);
+        assert_eq!(consumer.pop(), Some(0));
+        producer.push(7).unwrap();
+        assert_eq!(consumer.len(), 4);
+    }
+
+    #[test]
+    fn indices_wrap() {
+        let mut queue: Queue<u16, 8> = Queue::new();
+        let (mut producer, mut consumer) = queue.split();
+        for item in 0..1000 {
+            producer.push(item).unwrap();
+            producer.push(item + 1).unwrap();
+            assert_eq!(consumer.pop(), Some(item));
+            assert_eq!(consumer.pop(), Some(item + 1));
+        }
+        assert_eq!(consumer.take_overflows(), 0);
+    }
+
+    #[test]
+    fn keeps_the_order_of_a_full_queue_past_the_wrap() {
+        let mut queue: Queue<u16, 8> = Queue::new();
+        let (mut producer, mut consumer) = queue.split();
+        for item in 0..8 {
+            producer.push(item).unwrap();
+        }
+        for item in 8..600 {
+            assert_eq!(producer.push(item), Err(item));
+            assert_eq!(consumer.pop(), Some(item - 8));
+            producer.push(item).unwrap();
+            assert_eq!(consumer.len(), 8);
+        }
+        for item in 592..600 {
+            assert_eq!(consumer.pop(), Some(item));
+        }
+        assert!(consumer.is_empty());
+    }
+
+    #[test]
+    fn drops_the_items_left() {
+        use std::rc::Rc;
+
+        let item = Rc::new(());
+        {
+            let mut queue: Queue<Rc<()>, 4> = Queue::new();
+            let (mut producer, _) = queue.split();
+            producer.push(item.clone()).unwrap();
+            producer.push(item.clone()).unwrap();
+            assert_eq!(Rc::strong_count(&item), 3);
+        }
+        assert_eq!(Rc::strong_count(&item), 1);
+    }
+
+    #[test]
+    fn between_threads() {
+        use std::thread;
+
+        static mut QUEUE: Queue<u32, 16> = Queue::new();
+        let (mut producer, mut consumer) = unsafe { (*ptr::addr_of_mut!(QUEUE)).split() };
+        let pusher = thread::spawn(move || {
+            for item in 0..1000 {
+                while producer.push(item).is_err() {}
+            }
+        });
+        let mut expected = 0;
+        while expected < 1000 {
+            if let Some(item) = consumer.pop() {
+                assert_eq!(item, expected);
+                expected += 1;
+            }
+        }
+        pusher.join().unwrap();
+    }
+}
@@ -0,0 +1,156 @@
+//! Drivers for the battery backed real time clocks
+//!
+//! The DS1307 and the DS3231 share the address and the layout of the time
+//! registers, they differ in the control registers and the extras of the
+//! DS3231. Both are driven over the blocking I2C traits of embedded-hal.
+
+use embedded_hal::blocking::i2c::{Write, WriteRead};
+
+use crate::time::{Field, Time, TimeError, MIN_YEAR};
+
+pub mod ds1307;
+pub mod ds3231;
+
+pub use ds1307::Ds1307;
+pub use ds3231::Ds3231;
+
+/// I2C address of both chips
+pub const ADDRESS: u8 = 0x68;
+
+const TIME_REGISTERS: u8 = 0x00;
+const HOURS: u8 = 0x02;
+
+// Flags of the hours register
+const TWELVE_HOURS: u8 = 0b0100_0000;
+const PM: u8 = 0b0010_0000;
+
+pub trait Rtc {
+    /// Fails with `TimeError::NotSet` while the time was lost with the
+    /// battery, a time in 12 hour mode is switched to 24 hours
+    fn time(&mut self) -> Result<Time, TimeError>;
+    /// Sets the time in 24 hour mode
+    fn set_time(&mut self, time: &Time) -> Result<(), TimeError>;
+    /// Enables the 1 Hz square wave output that counts the seconds
+    fn enable_square_wave(&mut self) -> Result<(), TimeError>;
+}
+
+fn read_registers<I2C: WriteRead>(
+    i2c: &mut I2C,
+    register: u8,
+    buffer: &mut [u8],
+) -> Result<(), TimeError> {
+    i2c.write_read(ADDRESS, &[register], buffer)
+        .map_err(|_| TimeError::Bus)
+}
+
+/// Writes up to 8 registers starting at `register`
+fn write_registers<I2C: Write>(i2c: &mut I2C, register: u8, data: &[u8]) -> Result<(), TimeError> {
+    let mut bytes = [0; 9];
+    bytes[0] = register;
+    bytes[1..=data.len()].copy_from_slice(data);
+    i2c.write(ADDRESS, &bytes[..=data.len()])
+        .map_err(|_| TimeError::Bus)
+}
+
+/// Decodes the time registers read from the chip and rewrites the hours in
+/// 24 hour mode
+fn time_from_registers<I2C: Write>(i2c: &mut I2C, registers: &[u8]) -> Result<Time, TimeError> {
+    let time = decode_time(registers)?;
+    if registers[2] & TWELVE_HOURS != 0 {
+        write_registers(i2c, HOURS, &[to_bcd(time.hour())])?;
+    }
+    Ok(time)
+}
+
+fn write_time<I2C: Write>(i2c: &mut I2C, time: &Time) -> Result<(), TimeError> {
+    write_registers(i2c, TIME_REGISTERS, &encode_time(time))
+}
+
+pub(crate) fn from_bcd(value: u8, field: Field) -> Result<u8, TimeError> {
+    let (tens, ones) = (value >> 4, value & 0x0f);
+    if tens > 9 || ones > 9 {
+        return Err(TimeError::InvalidField(field));
+    }
+    Ok(tens * 10 + ones)
+}
+
+fn to_bcd(value: u8) -> u8 {
+    ((value / 10) << 4) | (value % 10)
+}
+
+/// Decodes the seconds to year registers at the start of `registers`, the
+/// flags in the upper bits of the seconds and month registers are ignored
+fn decode_time(registers: &[u8]) -> Result<Time, TimeError> {
+    let sec = from_bcd(registers[0] & 0x7f, Field::Second)?;
+    let min = from_bcd(registers[1] & 0x7f, Field::Minute)?;
+    let hour = if registers[2] & TWELVE_HOURS != 0 {
+        let hour = from_bcd(registers[2] & 0x1f, Field::Hour)?;
+        if !(1..=12).contains(&hour) {
+            return Err(TimeError::InvalidField(Field::Hour));
+        }
+        // 12 AM is midnight and 12 PM is noon
+        let pm = if registers[2] & PM != 0 { 12 } else { 0 };
+        hour % 12 + pm
+    } else {
+        from_bcd(registers[2] & 0x3f, Field::Hour)?
+    };
+    // registers[3] is the weekday, it follows from the date
+    let day = from_bcd(registers[4] & 0x3f, Field::Day)?;
+    let month = from_bcd(registers[5] & 0x1f, Field::Month)?;
+    let year = from_bcd(registers[6], Field::Year)?;
+    Time::new(MIN_YEAR + u16::from(year), month, day, hour, min, sec)
+}
+
+fn encode_time(time: &Time) -> [u8; 7] {
+    [
+        to_bcd(time.seconds()),
+        to_bcd(time.minutes()),
+        to_bcd(time.hour()),
+        time.weekday().number(),
+        to_bcd(time.day()),
+        to_bcd(time.month()),
+        to_bcd((time.year() - MIN_YEAR) as u8),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn time_registers_round_trip() {
+        let time = Time::new(2021, 12, 24, 18, 30, 59).unwrap();
+        let registers = encode_time(&time);
+        assert_eq!(registers, [0x59, 0x30, 0x18, 5, 0x24, 0x12, 0x21]);
+        assert_eq!(decode_time(&registers), Ok(time));
+    }
+
+    #[test]
+    fn rejects_invalid_bcd() {
+        let registers = [0x00, 0x0a, 0x12, 1, 0x01, 0x01, 0x21];
+        assert_eq!(
+            decode_time(&registers),
+            Err(TimeError::InvalidField(Field::Minute))
+        );
+        let registers = [0x00, 0x00, 0x12, 1, 0x31, 0x02, 0x21];
+        assert_eq!(
+            decode_time(&registers),
+            Err(TimeError::InvalidField(Field::Day))
+        );
+    }
+
+    #[test]
+    fn twelve_hour_mode() {
+        let hour = |flags: u8| {
+            let registers = [0x00, 0x15, TWELVE_HOURS | flags, 1, 0x01, 0x01, 0x21];
+            decode_time(&registers).map(|time| time.hour())
+        };
+        assert_eq!(hour(PM | 0x03), Ok(15));
+        assert_eq!(hour(0x03), Ok(3));
+        assert_eq!(hour(0x12), Ok(0));
+        assert_eq!(hour(PM | 0x12), Ok(12));
+        assert_eq!(hour(PM | 0x11), Ok(23));
+        assert_eq!(hour(0x00), Err(TimeError::InvalidField(Field::Hour)));
+        assert_eq!(hour(PM | 0x13), Err(TimeError::InvalidField(Field::Hour)));
+    }
+}
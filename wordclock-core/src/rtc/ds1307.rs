@@ -0,0 +1,62 @@
+use embedded_hal::blocking::i2c::{Write, WriteRead};
+
+use super::Rtc;
+use crate::time::{Time, TimeError};
+
+const SECONDS: u8 = 0x00;
+const CONTROL: u8 = 0x07;
+const RAM_START: u8 = 0x08;
+
+// Stops the oscillator, set after the first power up
+const CLOCK_HALT: u8 = 0b1000_0000;
+// Square wave output at 1 Hz
+const SQWE: u8 = 0b0001_0000;
+
+/// Written to the first byte of the RAM with the time, the RAM holds random
+/// values after the battery was empty
+pub const TIME_SET: u8 = 0x5a;
+
+pub struct Ds1307<I2C> {
+    i2c: I2C,
+}
+
+impl<I2C> Ds1307<I2C> {
+    pub fn new(i2c: I2C) -> Self {
+        Self { i2c }
+    }
+
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+}
+
+impl<I2C> Rtc for Ds1307<I2C>
+where
+    I2C: Write + WriteRead,
+{
+    fn time(&mut self) -> Result<Time, TimeError> {
+        // The time registers, the control register and the first RAM byte
+        let mut registers = [0; 9];
+        super::read_registers(&mut self.i2c, SECONDS, &mut registers)?;
+        if registers[0] & CLOCK_HALT != 0 {
+            // Restart the oscillator for the square wave, the time stays
+            // invalid until it is set
+            super::write_registers(&mut self.i2c, SECONDS, &[registers[0] & !CLOCK_HALT])?;
+            return Err(TimeError::NotSet);
+        }
+        if registers[usize::from(RAM_START)] != TIME_SET {
+            return Err(TimeError::NotSet);
+        }
+        super::time_from_registers(&mut self.i2c, &registers)
+    }
+
+    /// Also clears the clock halt bit
+    fn set_time(&mut self, time: &Time) -> Result<(), TimeError> {
+        super::write_time(&mut self.i2c, time)?;
+        super::write_registers(&mut self.i2c, RAM_START, &[TIME_SET])
+    }
+
+    fn enable_square_wave(&mut self) -> Result<(), TimeError> {
+        super::write_registers(&mut self.i2c, CONTROL, &[SQWE])
+    }
+}
@@ -0,0 +1,102 @@
+use embedded_hal::blocking::i2c::{Write, WriteRead};
+
+use super::Rtc;
+use crate::time::{Time, TimeError};
+
+const CONTROL: u8 = 0x0e;
+const STATUS: u8 = 0x0f;
+const AGING_OFFSET: u8 = 0x10;
+const TEMPERATURE: u8 = 0x11;
+
+// Cleared interrupt control bit, the pin puts out the square wave with the
+// rate select bits at 0 for 1 Hz
+const SQUARE_WAVE_1HZ: u8 = 0b0000_0000;
+// Set in the status register when the oscillator stopped, e.g. without power
+// and battery
+const OSCILLATOR_STOPPED: u8 = 0b1000_0000;
+
+/// Temperature compensated RTC, more accurate than the DS1307
+pub struct Ds3231<I2C> {
+    i2c: I2C,
+}
+
+impl<I2C> Ds3231<I2C> {
+    pub fn new(i2c: I2C) -> Self {
+        Self { i2c }
+    }
+
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+}
+
+impl<I2C> Ds3231<I2C>
+where
+    I2C: Write + WriteRead,
+{
+    /// Temperature of the chip in quarter degrees celsius, updated every 64 s
+    pub fn temperature(&mut self) -> Result<i16, TimeError> {
+        let mut registers = [0; 2];
+        super::read_registers(&mut self.i2c, TEMPERATURE, &mut registers)?;
+        Ok(temperature_from_registers(registers))
+    }
+
+    /// Offset added to the capacitance of the crystal, one step is about
+    /// 0.1 ppm at 25 °C and a positive offset slows the clock down
+    pub fn aging_offset(&mut self) -> Result<i8, TimeError> {
+        let mut register = [0; 1];
+        super::read_registers(&mut self.i2c, AGING_OFFSET, &mut register)?;
+        Ok(register[0] as i8)
+    }
+
+    pub fn set_aging_offset(&mut self, offset: i8) -> Result<(), TimeError> {
+        super::write_registers(&mut self.i2c, AGING_OFFSET, &[offset as u8])
+    }
+}
+
+impl<I2C> Rtc for Ds3231<I2C>
+where
+    I2C: Write + WriteRead,
+{
+    fn time(&mut self) -> Result<Time, TimeError> {
+        let mut status = [0; 1];
+        super::read_registers(&mut self.i2c, STATUS, &mut status)?;
+        if status[0] & OSCILLATOR_STOPPED != 0 {
+            return Err(TimeError::NotSet);
+        }
+        let mut registers = [0; 7];
+        super::read_registers(&mut self.i2c, super::TIME_REGISTERS, &mut registers)?;
+        super::time_from_registers(&mut self.i2c, &registers)
+    }
+
+    /// Also clears the oscillator stop flag, the other status bits like the
+    /// enabled 32 kHz output are kept
+    fn set_time(&mut self, time: &Time) -> Result<(), TimeError> {
+        super::write_time(&mut self.i2c, time)?;
+        let mut status = [0; 1];
+        super::read_registers(&mut self.i2c, STATUS, &mut status)?;
+        super::write_registers(&mut self.i2c, STATUS, &[status[0] & !OSCILLATOR_STOPPED])
+    }
+
+    fn enable_square_wave(&mut self) -> Result<(), TimeError> {
+        super::write_registers(&mut self.i2c, CONTROL, &[SQUARE_WAVE_1HZ])
+    }
+}
+
+/// The upper byte holds the signed degrees, the two upper bits of the lower
+/// byte the quarters
+fn temperature_from_registers(registers: [u8; 2]) -> i16 {
+    (i16::from(registers[0] as i8) << 2) | i16::from(registers[1] >> 6)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn temperature() {
+        assert_eq!(temperature_from_registers([25, 0b0100_0000]), 101);
+        assert_eq!(temperature_from_registers([0xff, 0b1100_0000]), -1);
+        assert_eq!(temperature_from_registers([0xf6, 0]), -40);
+    }
+}
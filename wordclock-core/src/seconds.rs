@@ -0,0 +1,166 @@
+//! Counting of the seconds between the readings of the time
+//!
+//! The square wave of the RTC ticks once per second. If it stops, e.g.
+//! because the RTC is missing, the millisecond timer counts the seconds
+//! instead, so the clock keeps running on its own.
+
+/// Without an edge of the square wave for this many milliseconds it counts
+/// as stopped
+const TIMEOUT_MS: u16 = 1500;
+
+pub struct SecondCounter {
+    seconds: u8,
+    since_edge_ms: u16,
+    timer_ms: u16,
+    /// The timer counted a second since the last edge
+    timer_counted: bool,
+}
+
+impl Default for SecondCounter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SecondCounter {
+    /// The timer counts until the first edge of the square wave
+    pub const fn new() -> Self {
+        Self {
+            seconds: 0,
+            since_edge_ms: TIMEOUT_MS,
+            timer_ms: 0,
+            timer_counted: false,
+        }
+    }
+
+    /// Rising edge of the square wave
+    pub fn on_square_wave(&mut self) {
+        // When the square wave returns in another phase, the timer may have
+        // counted the second of this edge already
+        let counted = self.timer_counted && self.timer_ms < 500;
+        self.since_edge_ms = 0;
+        self.timer_ms = 0;
+        self.timer_counted = false;
+        if !counted {
+            self.seconds = self.seconds.saturating_add(1);
+        }
+    }
+
+    /// Tick of the millisecond timer
+    pub fn on_millisecond(&mut self) {
+        self.since_edge_ms = self.since_edge_ms.saturating_add(1);
+        if self.square_wave_running() {
+            return;
+        }
+        if self.since_edge_ms == TIMEOUT_MS {
+            // The edges missed until the timeout are counted, the timer goes
+            // on in the phase of the square wave
+            let missed = (TIMEOUT_MS / 1000) as u8;
+            self.seconds = self.seconds.saturating_add(missed);
+            self.timer_ms = TIMEOUT_MS % 1000;
+            self.timer_counted = true;
+            return;
+        }
+        self.timer_ms += 1;
+        if self.timer_ms >= 1000 {
+            self.timer_ms = 0;
+            self.seconds = self.seconds.saturating_add(1);
+            self.timer_counted = true;
+        }
+    }
+
+    pub fn square_wave_running(&self) -> bool {
+        self.since_edge_ms < TIMEOUT_MS
+    }
+
+    pub fn seconds(&self) -> u8 {
+        self.seconds
+    }
+
+    pub fn set(&mut self, seconds: u8) {
+        self.seconds = seconds;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn milliseconds(counter: &mut SecondCounter, ms: u16) {
+        (0..ms).for_each(|_| counter.on_millisecond());
+    }
+
+    #[test]
+    fn counts_the_square_wave() {
+        let mut counter = SecondCounter::new();
+        for _ in 0..3 {
+            counter.on_square_wave();
+            milliseconds(&mut counter, 1000);
+        }
+        assert!(counter.square_wave_running());
+        assert_eq!(counter.seconds(), 3);
+    }
+
+    #[test]
+    fn timer_takes_over_when_the_square_wave_stops() {
+        let mut counter = SecondCounter::new();
+        counter.on_square_wave();
+        milliseconds(&mut counter, 1499);
+        assert!(counter.square_wave_running());
+        assert_eq!(counter.seconds(), 1);
+
+        // The edge after 1000 ms was missed
+        milliseconds(&mut counter, 1);
+        assert!(!counter.square_wave_running());
+        assert_eq!(counter.seconds(), 2);
+
+        // No second is lost, the next ones follow every 1000 ms
+        milliseconds(&mut counter, 499);
+        assert_eq!(counter.seconds(), 2);
+        milliseconds(&mut counter, 1);
+        assert_eq!(counter.seconds(), 3);
+        milliseconds(&mut counter, 2500);
+        assert_eq!(counter.seconds(), 5);
+    }
+
+    #[test]
+    fn square_wave_returns_in_another_phase() {
+        let mut counter = SecondCounter::new();
+        counter.on_square_wave();
+        milliseconds(&mut counter, 2000);
+        assert_eq!(counter.seconds(), 3);
+
+        // 200 ms after the timer counted a second
+        milliseconds(&mut counter, 200);
+        counter.on_square_wave();
+        assert_eq!(counter.seconds(), 3);
+        milliseconds(&mut counter, 1000);
+        counter.on_square_wave();
+        assert_eq!(counter.seconds(), 4);
+
+        // 700 ms after the timer counted a second
+        milliseconds(&mut counter, 1500);
+        assert_eq!(counter.seconds(), 5);
+        milliseconds(&mut counter, 700);
+        counter.on_square_wave();
+        assert_eq!(counter.seconds(), 6);
+    }
+
+    #[test]
+    fn timer_counts_without_square_wave() {
+        let mut counter = SecondCounter::new();
+        counter.set(58);
+        milliseconds(&mut counter, 2000);
+        assert_eq!(counter.seconds(), 60);
+    }
+
+    #[test]
+    fn square_wave_returns() {
+        let mut counter = SecondCounter::new();
+        milliseconds(&mut counter, 1500);
+        counter.on_square_wave();
+        assert!(counter.square_wave_running());
+        milliseconds(&mut counter, 1000);
+        assert_eq!(counter.seconds(), 2);
+    }
+}
@@ -0,0 +1,276 @@
+//! Arbitration between the sources of the time
+//!
+//! The RTC, DCF77 and the buttons all provide the time. Each of them
+//! implements `TimeSource` and the `Arbiter` decides which one sets the clock.
+
+use core::time::Duration;
+
+use crate::time::{Time, TimeError};
+
+pub trait TimeSource {
+    /// Short name for the serial log
+    fn name(&self) -> &'static str;
+    /// Checks the source for a new time, `Ok(None)` while there is none
+    fn poll(&mut self) -> Result<Option<Time>, TimeError>;
+    /// Last valid time returned by `poll`
+    fn last_valid(&self) -> Option<Time>;
+    /// Trust in the time of the source, higher wins and 0 never sets the clock
+    fn quality(&self) -> u8;
+}
+
+/// Time entered by the user, e.g. with the buttons
+#[derive(Default)]
+pub struct Manual {
+    pending: Option<Time>,
+    last_valid: Option<Time>,
+}
+
+impl Manual {
+    /// The user always overrides the other sources
+    pub const QUALITY: u8 = u8::MAX;
+
+    /// The time is handed to the arbiter on the next poll
+    pub fn set(&mut self, time: Time) {
+        self.pending = Some(time);
+    }
+}
+
+impl TimeSource for Manual {
+    fn name(&self) -> &'static str {
+        "manual"
+    }
+
+    fn poll(&mut self) -> Result<Option<Time>, TimeError> {
+        let time = self.pending.take();
+        if time.is_some() {
+            self.last_valid = time;
+        }
+        Ok(time)
+    }
+
+    fn last_valid(&self) -> Option<Time> {
+        self.last_valid
+    }
+
+    fn quality(&self) -> u8 {
+        Self::QUALITY
+    }
+}
+
+/// Picks the time of the best source
+///
+/// Of the sources with a new time the one with the highest quality wins. A
+/// time that is more than `max_jump` away from the clock is refused while a
+/// source with a higher quality provided a time within `max_age`.
+pub struct Arbiter {
+    max_age: Duration,
+    max_jump: Duration,
+    source: Option<&'static str>,
+}
+
+impl Arbiter {
+    pub fn new(max_age: Duration, max_jump: Duration) -> Self {
+        Self {
+            max_age,
+            max_jump,
+            source: None,
+        }
+    }
+
+    /// Name of the source that set the clock last
+    pub fn source(&self) -> Option<&'static str> {
+        self.source
+    }
+
+    /// Polls all sources and returns the time to set the clock to, if any
+    ///
+    /// `now` is the time of the clock, failures of the sources are passed to
+    /// `report` with the name of the source.
+    pub fn update(
+        &mut self,
+        now: &Time,
+        sources: &mut [&mut dyn TimeSource],
+        mut report: impl FnMut(&'static str, TimeError),
+    ) -> Option<Time> {
+        let mut best: Option<(Time, &'static str, u8)> = None;
+        for source in sources.iter_mut() {
+            match source.poll() {
+                Ok(Some(time)) => {
+                    let quality = source.quality();
+                    let better = match best {
+                        Some((_, _, best)) => quality > best,
+                        None => quality > 0,
+                    };
+                    if better {
+                        best = Some((time, source.name(), quality));
+                    }
+                }
+                Ok(None) => {}
+                Err(err) => report(source.name(), err),
+            }
+        }
+
+        let (time, name, quality) = best?;
+        if distance(&time, now) > self.max_jump && self.is_locked(now, sources, quality) {
+            report(name, TimeError::ImplausibleJump);
+            return None;
+        }
+        self.source = Some(name);
+        Some(time)
+    }
+
+    /// A source better than `quality` provided a time within `max_age`
+    fn is_locked(&self, now: &Time, sources: &[&mut dyn TimeSource], quality: u8) -> bool {
+        sources.iter().any(|source| {
+            source.quality() > quality
+                && matches!(source.last_valid(), Some(last) if distance(now, &last) <= self.max_age)
+        })
+    }
+}
+
+pub(crate) fn distance(a: &Time, b: &Time) -> Duration {
+    a.duration_since(b)
+        .or_else(|| b.duration_since(a))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: Duration = Duration::from_secs(60);
+
+    struct Fake {
+        name: &'static str,
+        quality: u8,
+        next: Option<Result<Time, TimeError>>,
+        last_valid: Option<Time>,
+    }
+
+    impl Fake {
+        fn new(name: &'static str, quality: u8) -> Self {
+            Self {
+                name,
+                quality,
+                next: None,
+                last_valid: None,
+            }
+        }
+    }
+
+    impl TimeSource for Fake {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn poll(&mut self) -> Result<Option<Time>, TimeError> {
+            let time = self.next.take().transpose()?;
+            if time.is_some() {
+                self.last_valid = time;
+            }
+            Ok(time)
+        }
+
+        fn last_valid(&self) -> Option<Time> {
+            self.last_valid
+        }
+
+        fn quality(&self) -> u8 {
+            self.quality
+        }
+    }
+
+    fn time(hour: u8, min: u8) -> Time {
+        Time::new(2021, 5, 1, hour, min, 0).unwrap()
+    }
+
+    fn arbiter() -> Arbiter {
+        Arbiter::new(60 * MINUTE, 2 * MINUTE)
+    }
+
+    #[test]
+    fn highest_quality_wins() {
+        let mut rtc = Fake::new("rtc", 1);
+        let mut dcf77 = Fake::new("dcf77", 2);
+        rtc.next = Some(Ok(time(12, 0)));
+        dcf77.next = Some(Ok(time(12, 1)));
+
+        let mut arbiter = arbiter();
+        let update = arbiter.update(&time(12, 0), &mut [&mut rtc, &mut dcf77], |_, _| {
+            panic!("no errors")
+        });
+        assert_eq!(update, Some(time(12, 1)));
+        assert_eq!(arbiter.source(), Some("dcf77"));
+    }
+
+    #[test]
+    fn reports_failed_sources() {
+        let mut rtc = Fake::new("rtc", 1);
+        rtc.next = Some(Err(TimeError::Bus));
+
+        let mut reported = None;
+        let update = arbiter().update(&time(12, 0), &mut [&mut rtc], |name, err| {
+            reported = Some((name, err))
+        });
+        assert_eq!(update, None);
+        assert_eq!(reported, Some(("rtc", TimeError::Bus)));
+    }
+
+    #[test]
+    fn refuses_jumps_of_worse_sources_until_the_better_one_is_stale() {
+        let mut rtc = Fake::new("rtc", 1);
+        let mut dcf77 = Fake::new("dcf77", 2);
+        let mut arbiter = arbiter();
+        dcf77.next = Some(Ok(time(12, 0)));
+        arbiter.update(&time(12, 0), &mut [&mut rtc, &mut dcf77], |_, _| {});
+
+        let mut reported = None;
+        rtc.next = Some(Ok(time(17, 0)));
+        let update = arbiter.update(&time(12, 30), &mut [&mut rtc, &mut dcf77], |name, err| {
+            reported = Some((name, err))
+        });
+        assert_eq!(update, None);
+        assert_eq!(reported, Some(("rtc", TimeError::ImplausibleJump)));
+        assert_eq!(arbiter.source(), Some("dcf77"));
+
+        // Small corrections are fine
+        rtc.next = Some(Ok(time(12, 31)));
+        let update = arbiter.update(&time(12, 30), &mut [&mut rtc, &mut dcf77], |_, _| {});
+        assert_eq!(update, Some(time(12, 31)));
+
+        // The rtc set the clock last, dcf77 still holds the lock
+        rtc.next = Some(Ok(time(17, 0)));
+        let update = arbiter.update(&time(12, 32), &mut [&mut rtc, &mut dcf77], |_, _| {});
+        assert_eq!(update, None);
+    }
+
+    #[test]
+    fn accepts_jumps_after_the_better_source_is_stale() {
+        let mut rtc = Fake::new("rtc", 1);
+        let mut dcf77 = Fake::new("dcf77", 2);
+        let mut arbiter = arbiter();
+        dcf77.next = Some(Ok(time(12, 0)));
+        arbiter.update(&time(12, 0), &mut [&mut rtc, &mut dcf77], |_, _| {});
+
+        rtc.next = Some(Ok(time(17, 0)));
+        let update = arbiter.update(&time(13, 1), &mut [&mut rtc, &mut dcf77], |_, _| {});
+        assert_eq!(update, Some(time(17, 0)));
+        assert_eq!(arbiter.source(), Some("rtc"));
+    }
+
+    #[test]
+    fn manual_time_overrides_everything() {
+        let mut manual = Manual::default();
+        let mut dcf77 = Fake::new("dcf77", 2);
+        let mut arbiter = arbiter();
+        dcf77.next = Some(Ok(time(12, 0)));
+        arbiter.update(&time(12, 0), &mut [&mut manual, &mut dcf77], |_, _| {});
+
+        manual.set(time(18, 0));
+        dcf77.next = Some(Ok(time(12, 1)));
+        let update = arbiter.update(&time(12, 0), &mut [&mut manual, &mut dcf77], |_, _| {});
+        assert_eq!(update, Some(time(18, 0)));
+        assert_eq!(arbiter.source(), Some("manual"));
+        assert_eq!(manual.poll(), Ok(None));
+    }
+}
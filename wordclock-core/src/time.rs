@@ -0,0 +1,463 @@
+//! Date and time of day of the clock
+//!
+//! Covers the years 2000 to 2099, the range of the two digit years of the RTC
+//! and of DCF77. Every `Time` is a valid date, the constructors reject
+//! impossible values.
+
+use core::convert::TryFrom;
+use core::fmt;
+use core::ops::{Add, Sub};
+use core::time::Duration;
+
+use crate::dcf77::Parity;
+
+pub const MIN_YEAR: u16 = 2000;
+pub const MAX_YEAR: u16 = 2099;
+
+const SECONDS_PER_DAY: u32 = 24 * 60 * 60;
+/// Seconds from 2000 to the end of 2099, 25 leap years
+const SECONDS_OF_RANGE: u32 = (100 * 365 + 25) * SECONDS_PER_DAY;
+
+/// Days of the year before the first of each month in a common year
+const DAYS_BEFORE_MONTH: [u16; 12] = [0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334];
+
+// `is_multiple_of` and `div_ceil` are newer than the AVR toolchain
+#[allow(clippy::manual_is_multiple_of)]
+pub fn is_leap_year(year: u16) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+pub fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+fn days_in_year(year: u16) -> u16 {
+    if is_leap_year(year) {
+        366
+    } else {
+        365
+    }
+}
+
+/// Field of a date and time of day
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Field {
+    Year,
+    Month,
+    Day,
+    Hour,
+    Minute,
+    Second,
+    Weekday,
+}
+
+/// Why a time source could not provide a time
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum TimeError {
+    /// The bus to the device failed
+    Bus,
+    /// The device lost the time, e.g. with an empty battery
+    NotSet,
+    /// A field was out of range or no valid BCD value
+    InvalidField(Field),
+    /// A DCF77 frame missed bits or had a pulse out of tolerance
+    Incomplete,
+    /// The start bit of a DCF77 frame was not set
+    StartBit,
+    /// A parity bit of a DCF77 frame did not match
+    Parity(Parity),
+    /// Not exactly one of the zone bits of a DCF77 frame was set
+    Zone,
+    /// A DCF77 frame contradicts the previous one
+    Inconsistent,
+    /// The time does not follow the previous one
+    ImplausibleJump,
+}
+
+impl TimeError {
+    /// Short description for the serial log
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimeError::Bus => "bus error",
+            TimeError::NotSet => "time not set",
+            TimeError::InvalidField(Field::Year) => "invalid year",
+            TimeError::InvalidField(Field::Month) => "invalid month",
+            TimeError::InvalidField(Field::Day) => "invalid day",
+            TimeError::InvalidField(Field::Hour) => "invalid hour",
+            TimeError::InvalidField(Field::Minute) => "invalid minute",
+            TimeError::InvalidField(Field::Second) => "invalid second",
+            TimeError::InvalidField(Field::Weekday) => "invalid weekday",
+            TimeError::Incomplete => "incomplete frame",
+            TimeError::StartBit => "missing start bit",
+            TimeError::Parity(Parity::Minute) => "minute parity error",
+            TimeError::Parity(Parity::Hour) => "hour parity error",
+            TimeError::Parity(Parity::Date) => "date parity error",
+            TimeError::Zone => "invalid zone bits",
+            TimeError::Inconsistent => "inconsistent frames",
+            TimeError::ImplausibleJump => "implausible jump",
+        }
+    }
+}
+
+impl fmt::Display for TimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Day of the week, numbered from Monday as 1 to Sunday as 7 like in DCF77
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Weekday {
+    Monday = 1,
+    Tuesday = 2,
+    Wednesday = 3,
+    Thursday = 4,
+    Friday = 5,
+    Saturday = 6,
+    Sunday = 7,
+}
+
+impl Weekday {
+    const ALL: [Weekday; 7] = [
+        Weekday::Monday,
+        Weekday::Tuesday,
+        Weekday::Wednesday,
+        Weekday::Thursday,
+        Weekday::Friday,
+        Weekday::Saturday,
+        Weekday::Sunday,
+    ];
+
+    pub fn number(self) -> u8 {
+        self as u8
+    }
+
+    pub fn from_number(number: u8) -> Option<Self> {
+        Self::ALL.get(usize::from(number).checked_sub(1)?).copied()
+    }
+}
+
+// The fields are ordered by significance for the derived ordering
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+pub struct Time {
+    year: u16,
+    month: u8,
+    day: u8,
+    hour: u8,
+    min: u8,
+    sec: u8,
+}
+
+impl Default for Time {
+    /// Midnight at the start of 2000-01-01
+    fn default() -> Self {
+        Self {
+            year: MIN_YEAR,
+            month: 1,
+            day: 1,
+            hour: 0,
+            min: 0,
+            sec: 0,
+        }
+    }
+}
+
+impl Time {
+    /// Fails with the first field that is out of range
+    pub fn new(
+        year: u16,
+        month: u8,
+        day: u8,
+        hour: u8,
+        min: u8,
+        sec: u8,
+    ) -> Result<Self, TimeError> {
+        let checks = [
+            ((MIN_YEAR..=MAX_YEAR).contains(&year), Field::Year),
+            ((1..=12).contains(&month), Field::Month),
+            ((1..=days_in_month(year, month)).contains(&day), Field::Day),
+            (hour < 24, Field::Hour),
+            (min < 60, Field::Minute),
+            (sec < 60, Field::Second),
+        ];
+        if let Some((_, field)) = checks.iter().find(|(valid, _)| !valid) {
+            return Err(TimeError::InvalidField(*field));
+        }
+        Ok(Self {
+            year,
+            month,
+            day,
+            hour,
+            min,
+            sec,
+        })
+    }
+
+    /// Time from the seconds since 2000-01-01 00:00:00
+    pub fn from_timestamp(timestamp: u32) -> Option<Self> {
+        let mut days = timestamp / SECONDS_PER_DAY;
+        let seconds = timestamp % SECONDS_PER_DAY;
+
+        let mut year = MIN_YEAR;
+        while days >= u32::from(days_in_year(year)) {
+            days -= u32::from(days_in_year(year));
+            year += 1;
+            if year > MAX_YEAR {
+                return None;
+            }
+        }
+        let mut month = 1;
+        while days >= u32::from(days_in_month(year, month)) {
+            days -= u32::from(days_in_month(year, month));
+            month += 1;
+        }
+
+        Some(Self {
+            year,
+            month,
+            day: days as u8 + 1,
+            hour: (seconds / 3600) as u8,
+            min: (seconds / 60 % 60) as u8,
+            sec: (seconds % 60) as u8,
+        })
+    }
+
+    /// Seconds since 2000-01-01 00:00:00
+    pub fn timestamp(&self) -> u32 {
+        self.days_since_2000() * SECONDS_PER_DAY
+            + u32::from(self.hour) * 3600
+            + u32::from(self.min) * 60
+            + u32::from(self.sec)
+    }
+
+    #[allow(clippy::manual_div_ceil)]
+    fn days_since_2000(&self) -> u32 {
+        let years = u32::from(self.year - MIN_YEAR);
+        // Every fourth year is a leap year between 2000 and 2099, starting
+        // with 2000 itself
+        let mut days = years * 365 + (years + 3) / 4;
+        days += u32::from(DAYS_BEFORE_MONTH[usize::from(self.month - 1)]);
+        if self.month > 2 && is_leap_year(self.year) {
+            days += 1;
+        }
+        days + u32::from(self.day - 1)
+    }
+
+    pub fn minutes(&self) -> u8 {
+        self.min
+    }
+
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    pub fn seconds(&self) -> u8 {
+        self.sec
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    pub fn weekday(&self) -> Weekday {
+        // 2000-01-01 was a saturday
+        Weekday::ALL[((self.days_since_2000() + 5) % 7) as usize]
+    }
+
+    /// Returns `None` if the result is after 2099, fractions of a second are
+    /// dropped
+    pub fn checked_add(&self, duration: Duration) -> Option<Self> {
+        let seconds = u32::try_from(duration.as_secs()).ok()?;
+        Self::from_timestamp(self.timestamp().checked_add(seconds)?)
+    }
+
+    /// Returns `None` if the result is before 2000, fractions of a second are
+    /// dropped
+    pub fn checked_sub(&self, duration: Duration) -> Option<Self> {
+        let seconds = u32::try_from(duration.as_secs()).ok()?;
+        Self::from_timestamp(self.timestamp().checked_sub(seconds)?)
+    }
+
+    /// Returns `None` if `earlier` is after this time
+    pub fn duration_since(&self, earlier: &Time) -> Option<Duration> {
+        let seconds = self.timestamp().checked_sub(earlier.timestamp())?;
+        Some(Duration::from_secs(seconds.into()))
+    }
+
+    /// Wraps to the start of 2000 after the end of 2099
+    pub fn inc_minutes(&self) -> Self {
+        self.wrapping_add_secs(60)
+    }
+
+    /// Wraps to the start of 2000 after the end of 2099
+    pub fn inc_hours(&self) -> Self {
+        self.wrapping_add_secs(60 * 60)
+    }
+
+    fn wrapping_add_secs(&self, seconds: u32) -> Self {
+        let timestamp = (self.timestamp() + seconds) % SECONDS_OF_RANGE;
+        Self::from_timestamp(timestamp).expect("timestamp within the supported range")
+    }
+}
+
+impl Add<Duration> for Time {
+    type Output = Time;
+
+    fn add(self, duration: Duration) -> Time {
+        self.checked_add(duration)
+            .expect("overflow when adding duration to time")
+    }
+}
+
+impl Sub<Duration> for Time {
+    type Output = Time;
+
+    fn sub(self, duration: Duration) -> Time {
+        self.checked_sub(duration)
+            .expect("overflow when subtracting duration from time")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(year: u16, month: u8, day: u8, hour: u8, min: u8, sec: u8) -> Time {
+        Time::new(year, month, day, hour, min, sec).unwrap()
+    }
+
+    #[test]
+    fn inc_minutes_wraps_hour_and_day() {
+        let next = time(2021, 3, 1, 10, 59, 0).inc_minutes();
+        assert_eq!((next.hour(), next.minutes()), (11, 0));
+
+        let next = time(2021, 3, 1, 23, 59, 0).inc_minutes();
+        assert_eq!(next, time(2021, 3, 2, 0, 0, 0));
+    }
+
+    #[test]
+    fn inc_hours_wraps_at_midnight() {
+        let next = time(2021, 3, 1, 23, 15, 0).inc_hours();
+        assert_eq!(next, time(2021, 3, 2, 0, 15, 0));
+    }
+
+    #[test]
+    fn rolls_over_month_and_year() {
+        assert_eq!(
+            time(2021, 2, 28, 23, 59, 0).inc_minutes(),
+            time(2021, 3, 1, 0, 0, 0)
+        );
+        assert_eq!(
+            time(2024, 2, 28, 23, 59, 0).inc_minutes(),
+            time(2024, 2, 29, 0, 0, 0)
+        );
+        assert_eq!(
+            time(2021, 12, 31, 23, 59, 0).inc_minutes(),
+            time(2022, 1, 1, 0, 0, 0)
+        );
+    }
+
+    #[test]
+    fn wraps_at_the_end_of_the_range() {
+        assert_eq!(
+            time(2099, 12, 31, 23, 59, 0).inc_minutes(),
+            time(2000, 1, 1, 0, 0, 0)
+        );
+        assert_eq!(
+            time(2099, 12, 31, 23, 30, 15).inc_hours(),
+            time(2000, 1, 1, 0, 30, 15)
+        );
+        assert_eq!(
+            time(2099, 12, 31, 23, 58, 0).inc_minutes(),
+            time(2099, 12, 31, 23, 59, 0)
+        );
+    }
+
+    #[test]
+    fn rejects_impossible_values() {
+        let invalid = |field| Err(TimeError::InvalidField(field));
+        assert_eq!(Time::new(2021, 2, 29, 0, 0, 0), invalid(Field::Day));
+        assert!(Time::new(2024, 2, 29, 0, 0, 0).is_ok());
+        assert_eq!(Time::new(2021, 4, 31, 0, 0, 0), invalid(Field::Day));
+        assert_eq!(Time::new(2021, 0, 1, 0, 0, 0), invalid(Field::Month));
+        assert_eq!(Time::new(2021, 13, 1, 0, 0, 0), invalid(Field::Month));
+        assert_eq!(Time::new(2021, 1, 0, 0, 0, 0), invalid(Field::Day));
+        assert_eq!(Time::new(2021, 1, 1, 24, 0, 0), invalid(Field::Hour));
+        assert_eq!(Time::new(2021, 1, 1, 0, 60, 0), invalid(Field::Minute));
+        assert_eq!(Time::new(2021, 1, 1, 0, 0, 60), invalid(Field::Second));
+        assert_eq!(Time::new(1999, 12, 31, 0, 0, 0), invalid(Field::Year));
+        assert_eq!(Time::new(2100, 1, 1, 0, 0, 0), invalid(Field::Year));
+    }
+
+    #[test]
+    fn leap_years() {
+        assert!(is_leap_year(2000));
+        assert!(is_leap_year(2024));
+        assert!(!is_leap_year(2021));
+        assert!(!is_leap_year(2100));
+    }
+
+    #[test]
+    fn weekdays() {
+        assert_eq!(Time::default().weekday(), Weekday::Saturday);
+        assert_eq!(time(2021, 1, 1, 12, 0, 0).weekday(), Weekday::Friday);
+        assert_eq!(time(2024, 2, 29, 0, 0, 0).weekday(), Weekday::Thursday);
+        assert_eq!(time(2099, 12, 31, 0, 0, 0).weekday(), Weekday::Thursday);
+        assert_eq!(Weekday::from_number(7), Some(Weekday::Sunday));
+        assert_eq!(Weekday::from_number(0), None);
+        assert_eq!(Weekday::from_number(8), None);
+    }
+
+    #[test]
+    fn timestamp_round_trip() {
+        for date in [
+            Time::default(),
+            time(2000, 2, 29, 1, 2, 3),
+            time(2021, 3, 28, 1, 0, 0),
+            time(2099, 12, 31, 23, 59, 59),
+        ]
+        .iter()
+        {
+            assert_eq!(Time::from_timestamp(date.timestamp()), Some(*date));
+        }
+        assert_eq!(time(2000, 1, 2, 0, 0, 1).timestamp(), 86401);
+        assert_eq!(
+            Time::from_timestamp(time(2099, 12, 31, 23, 59, 59).timestamp() + 1),
+            None
+        );
+    }
+
+    #[test]
+    fn ordered_by_date_and_time() {
+        assert!(time(2021, 1, 1, 23, 59, 59) < time(2021, 1, 2, 0, 0, 0));
+        assert!(time(2020, 12, 31, 0, 0, 0) < time(2021, 1, 1, 0, 0, 0));
+        assert!(time(2021, 5, 1, 0, 0, 1) > time(2021, 5, 1, 0, 0, 0));
+    }
+
+    #[test]
+    fn add_and_subtract_durations() {
+        let start = time(2021, 10, 31, 0, 30, 0);
+        let later = start + Duration::from_secs(2 * 24 * 3600 + 90);
+        assert_eq!(later, time(2021, 11, 2, 0, 31, 30));
+        assert_eq!(later - Duration::from_secs(2 * 24 * 3600 + 90), start);
+        assert_eq!(
+            later.duration_since(&start),
+            Some(Duration::from_secs(2 * 24 * 3600 + 90))
+        );
+        assert_eq!(start.duration_since(&later), None);
+        assert_eq!(Time::default().checked_sub(Duration::from_secs(1)), None);
+    }
+}
@@ -0,0 +1,187 @@
+//! Millisecond timebase and software timers
+//!
+//! The counter of the milliseconds is a `u32`, which wraps after about 49
+//! days. `Instant` and the timers compare with wrapping arithmetic, so they
+//! keep working across the wrap as long as the intervals stay below half of
+//! it.
+
+use core::ops::{Add, Sub};
+
+/// Milliseconds between two instants
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Duration(u32);
+
+impl Duration {
+    pub const fn from_millis(millis: u32) -> Self {
+        Self(millis)
+    }
+
+    pub const fn from_secs(secs: u32) -> Self {
+        Self(secs * 1000)
+    }
+
+    pub fn as_millis(self) -> u32 {
+        self.0
+    }
+}
+
+/// Reading of the millisecond counter
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Instant(u32);
+
+impl Instant {
+    pub const fn from_millis(millis: u32) -> Self {
+        Self(millis)
+    }
+
+    pub fn millis(self) -> u32 {
+        self.0
+    }
+
+    /// Time passed since the earlier instant
+    pub fn duration_since(self, earlier: Instant) -> Duration {
+        Duration(self.0.wrapping_sub(earlier.0))
+    }
+
+    /// True if the instant is the deadline or later
+    pub fn reached(self, deadline: Instant) -> bool {
+        (self.0.wrapping_sub(deadline.0) as i32) >= 0
+    }
+}
+
+impl Add<Duration> for Instant {
+    type Output = Instant;
+
+    fn add(self, duration: Duration) -> Instant {
+        Instant(self.0.wrapping_add(duration.0))
+    }
+}
+
+impl Sub<Instant> for Instant {
+    type Output = Duration;
+
+    fn sub(self, earlier: Instant) -> Duration {
+        self.duration_since(earlier)
+    }
+}
+
+/// Software timer polled from the main loop
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Timer {
+    deadline: Option<Instant>,
+    /// Set for periodic timers
+    period: Option<Duration>,
+}
+
+impl Timer {
+    /// A stopped timer
+    pub const fn new() -> Self {
+        Self {
+            deadline: None,
+            period: None,
+        }
+    }
+
+    /// Expires once after the duration
+    pub fn start(&mut self, now: Instant, after: Duration) {
+        self.deadline = Some(now + after);
+        self.period = None;
+    }
+
+    /// Expires every period, starting one period from now
+    pub fn start_periodic(&mut self, now: Instant, period: Duration) {
+        self.deadline = Some(now + period);
+        self.period = Some(period);
+    }
+
+    pub fn stop(&mut self) {
+        self.deadline = None;
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.deadline.is_some()
+    }
+
+    /// Returns true once per expiry
+    ///
+    /// Periods missed because the timer was polled too late are dropped
+    /// instead of expiring in a burst.
+    pub fn poll(&mut self, now: Instant) -> bool {
+        let deadline = match self.deadline {
+            Some(deadline) if now.reached(deadline) => deadline,
+            _ => return false,
+        };
+        self.deadline = self.period.map(|period| {
+            let next = deadline + period;
+            if now.reached(next) {
+                now + period
+            } else {
+                next
+            }
+        });
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(millis: u32) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn instants_across_the_wrap() {
+        let before = Instant::from_millis(u32::MAX - 9);
+        let after = before + ms(20);
+        assert_eq!(after.millis(), 10);
+        assert_eq!(after - before, ms(20));
+        assert!(after.reached(before));
+        assert!(!before.reached(after));
+        assert!(before.reached(before));
+    }
+
+    #[test]
+    fn one_shot_expires_once() {
+        let start = Instant::from_millis(100);
+        let mut timer = Timer::new();
+        assert!(!timer.poll(start));
+
+        timer.start(start, ms(50));
+        assert!(!timer.poll(start + ms(49)));
+        assert!(timer.poll(start + ms(50)));
+        assert!(!timer.is_running());
+        assert!(!timer.poll(start + ms(100)));
+    }
+
+    #[test]
+    fn periodic_keeps_its_phase() {
+        let start = Instant::from_millis(u32::MAX - 15);
+        let mut timer = Timer::new();
+        timer.start_periodic(start, ms(10));
+        assert!(timer.poll(start + ms(12)));
+        assert!(!timer.poll(start + ms(19)));
+        assert!(timer.poll(start + ms(20)));
+        assert!(timer.is_running());
+    }
+
+    #[test]
+    fn periodic_drops_missed_periods() {
+        let start = Instant::from_millis(0);
+        let mut timer = Timer::new();
+        timer.start_periodic(start, ms(10));
+        assert!(timer.poll(start + ms(55)));
+        assert!(!timer.poll(start + ms(64)));
+        assert!(timer.poll(start + ms(65)));
+    }
+
+    #[test]
+    fn stop() {
+        let start = Instant::from_millis(0);
+        let mut timer = Timer::new();
+        timer.start_periodic(start, ms(10));
+        timer.stop();
+        assert!(!timer.poll(start + ms(10)));
+    }
+}
@@ -0,0 +1,140 @@
+//! Central european time with the daylight saving time rules of the EU
+//!
+//! The RTC keeps UTC, the clock shows the local time. Summer time starts and
+//! ends on the last sunday of march and october at 01:00 UTC, so the local
+//! time stays right without DCF77 reception.
+
+use core::time::Duration;
+
+use crate::time::{days_in_month, Time};
+
+const HOUR: Duration = Duration::from_secs(60 * 60);
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Zone {
+    /// Central european time, UTC+1
+    Cet,
+    /// Central european summer time, UTC+2
+    Cest,
+}
+
+impl Zone {
+    /// Offset of the local time to UTC
+    pub fn offset(self) -> Duration {
+        match self {
+            Zone::Cet => HOUR,
+            Zone::Cest => 2 * HOUR,
+        }
+    }
+}
+
+/// 01:00 UTC on the last sunday of the month
+fn switch_time(year: u16, month: u8) -> Time {
+    let last_day = Time::new(year, month, days_in_month(year, month), 1, 0, 0)
+        .expect("last day of the month is a valid date");
+    // Sunday is number 7, the other days count from monday as 1
+    let days_after_sunday = last_day.weekday().number() % 7;
+    last_day - Duration::from_secs(u64::from(days_after_sunday) * 24 * HOUR.as_secs())
+}
+
+/// Zone in effect at the UTC time
+pub fn zone(utc: &Time) -> Zone {
+    let year = utc.year();
+    if switch_time(year, 3) <= *utc && *utc < switch_time(year, 10) {
+        Zone::Cest
+    } else {
+        Zone::Cet
+    }
+}
+
+/// Local time for the UTC time, stays at the UTC time at the very end of the
+/// supported range
+pub fn to_local(utc: &Time) -> Time {
+    to_local_in(utc, zone(utc))
+}
+
+/// Local time for the UTC time in the zone, e.g. as announced by DCF77
+pub fn to_local_in(utc: &Time, zone: Zone) -> Time {
+    utc.checked_add(zone.offset()).unwrap_or(*utc)
+}
+
+/// UTC time for the local time in the zone, like DCF77 sends it
+///
+/// Without a zone it is derived from the rules. The repeated hour at the end
+/// of summer time is then taken as standard time.
+pub fn to_utc(local: &Time, zone: Option<Zone>) -> Option<Time> {
+    let zone = match zone {
+        Some(zone) => zone,
+        None => self::zone(&local.checked_sub(Zone::Cet.offset())?),
+    };
+    local.checked_sub(zone.offset())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(year: u16, month: u8, day: u8, hour: u8, min: u8) -> Time {
+        Time::new(year, month, day, hour, min, 0).unwrap()
+    }
+
+    #[test]
+    fn summer_time_starts_on_last_sunday_of_march() {
+        assert_eq!(zone(&time(2021, 3, 28, 0, 59)), Zone::Cet);
+        assert_eq!(zone(&time(2021, 3, 28, 1, 0)), Zone::Cest);
+        assert_eq!(
+            to_local(&time(2021, 3, 28, 0, 59)),
+            time(2021, 3, 28, 1, 59)
+        );
+        assert_eq!(to_local(&time(2021, 3, 28, 1, 0)), time(2021, 3, 28, 3, 0));
+    }
+
+    #[test]
+    fn summer_time_ends_on_last_sunday_of_october() {
+        assert_eq!(zone(&time(2021, 10, 31, 0, 59)), Zone::Cest);
+        assert_eq!(zone(&time(2021, 10, 31, 1, 0)), Zone::Cet);
+        assert_eq!(
+            to_local(&time(2021, 10, 31, 0, 59)),
+            time(2021, 10, 31, 2, 59)
+        );
+        assert_eq!(
+            to_local(&time(2021, 10, 31, 1, 0)),
+            time(2021, 10, 31, 2, 0)
+        );
+    }
+
+    #[test]
+    fn switch_on_the_last_day_of_the_month() {
+        // The 31st of march 2024 and of october 2021 are sundays
+        assert_eq!(switch_time(2024, 3), time(2024, 3, 31, 1, 0));
+        assert_eq!(switch_time(2021, 10), time(2021, 10, 31, 1, 0));
+        assert_eq!(switch_time(2022, 3), time(2022, 3, 27, 1, 0));
+    }
+
+    #[test]
+    fn local_time_crosses_the_date() {
+        assert_eq!(
+            to_local(&time(2021, 12, 31, 23, 30)),
+            time(2022, 1, 1, 0, 30)
+        );
+    }
+
+    #[test]
+    fn utc_from_local_time() {
+        let local = time(2021, 10, 31, 2, 30);
+        assert_eq!(
+            to_utc(&local, Some(Zone::Cest)),
+            Some(time(2021, 10, 31, 0, 30))
+        );
+        assert_eq!(
+            to_utc(&local, Some(Zone::Cet)),
+            Some(time(2021, 10, 31, 1, 30))
+        );
+        assert_eq!(to_utc(&local, None), Some(time(2021, 10, 31, 1, 30)));
+        assert_eq!(
+            to_utc(&time(2021, 7, 1, 12, 0), None),
+            Some(time(2021, 7, 1, 10, 0))
+        );
+        assert_eq!(to_utc(&time(2000, 1, 1, 0, 30), Some(Zone::Cet)), None);
+    }
+}
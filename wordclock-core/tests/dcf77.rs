@@ -0,0 +1,216 @@
+use core::time::Duration;
+use wordclock_core::dcf77::pulse::{Pulse, PulseDecoder};
+
+use wordclock_core::dcf77::validate::{decode, Validator};
+use wordclock_core::dcf77::{Frame, Receiver};
+use wordclock_core::time::{Time, TimeError};
+use wordclock_core::timebase::Instant;
+use wordclock_core::timezone::{self, Zone};
+
+/// Frame as sent for the local time
+fn encode(local: &Time, zone: Zone) -> Frame {
+    let mut bits = 0u64;
+    let mut field = |start: u32, value: u8, len: u32| {
+        let bcd = u64::from(((value / 10) << 4) | (value % 10));
+        assert!(bcd < 1 << len);
+        bits |= bcd << start;
+    };
+    field(21, local.minutes(), 7);
+    field(29, local.hour(), 6);
+    field(36, local.day(), 6);
+    field(42, local.weekday().number(), 3);
+    field(45, local.month(), 5);
+    field(50, (local.year() - 2000) as u8, 8);
+    // Even parity of the bits since the previous parity bit
+    for &(start, parity) in [(21, 28), (29, 35), (36, 58)].iter() {
+        let ones = (bits >> start & ((1 << (parity - start)) - 1)).count_ones();
+        bits |= u64::from(ones % 2) << parity;
+    }
+    bits |= 1 << 20;
+    bits |= match zone {
+        Zone::Cest => 1 << 17,
+        Zone::Cet => 1 << 18,
+    };
+    Frame(bits)
+}
+
+/// Edges of the receiver for the frame starting at the time in milliseconds,
+/// the minute gap follows the last second
+fn edges(frame: &Frame, start: u32) -> Vec<(bool, Instant)> {
+    let mut edges = Vec::new();
+    for second in 0..59 {
+        let rise = start + second * 1000;
+        let width = if frame.0 >> second & 1 == 1 { 200 } else { 100 };
+        edges.push((true, Instant::from_millis(rise)));
+        edges.push((false, Instant::from_millis(rise + width)));
+    }
+    edges
+}
+
+fn receive(receiver: &mut Receiver, edges: &[(bool, Instant)]) -> Vec<Frame> {
+    edges
+        .iter()
+        .filter_map(|(high, at)| receiver.on_edge(*high, *at))
+        .filter_map(|frame| frame.ok())
+        .collect()
+}
+
+fn local(min: u8) -> Time {
+    Time::new(2021, 10, 31, 2, min, 0).unwrap()
+}
+
+#[test]
+fn receives_frames_from_edges() {
+    let frames = [
+        encode(&local(58), Zone::Cest),
+        encode(&local(59), Zone::Cest),
+    ];
+    let mut all = Vec::new();
+    // Some bits of the minute before
+    for second in 40..59 {
+        all.push((true, Instant::from_millis(second * 1000)));
+        all.push((false, Instant::from_millis(second * 1000 + 100)));
+    }
+    all.extend(edges(&frames[0], 60_000));
+    all.extend(edges(&frames[1], 120_000));
+    // The first pulse of the next minute completes the last frame
+    all.push((true, Instant::from_millis(180_000)));
+    all.push((false, Instant::from_millis(180_100)));
+
+    let mut receiver = Receiver::new();
+    assert_eq!(receive(&mut receiver, &all), frames.to_vec());
+    assert_eq!(frames[0].zone(), Some(Zone::Cest));
+    assert_eq!((receiver.stats.frames(), receiver.stats.failed()), (2, 1));
+    assert_eq!(receiver.stats.last_minute().pulses, 59);
+    assert_eq!(receiver.stats.bars(Instant::from_millis(180_100)), 4);
+}
+
+#[test]
+fn receives_frames_through_noise() {
+    let frame = encode(&local(7), Zone::Cet);
+    let mut all = vec![
+        (true, Instant::from_millis(58_000)),
+        (false, Instant::from_millis(58_100)),
+    ];
+    for (index, edge) in edges(&frame, 60_000).into_iter().enumerate() {
+        let at = edge.1.millis();
+        all.push(edge);
+        match index % 6 {
+            // Spike between the pulses
+            1 => {
+                all.push((true, Instant::from_millis(at + 400)));
+                all.push((false, Instant::from_millis(at + 402)));
+            }
+            // Dropout within the pulse
+            4 => {
+                all.push((false, Instant::from_millis(at + 40)));
+                all.push((true, Instant::from_millis(at + 45)));
+            }
+            _ => (),
+        }
+    }
+    all.push((true, Instant::from_millis(120_000)));
+    all.push((false, Instant::from_millis(120_100)));
+
+    let mut receiver = Receiver::new();
+    assert_eq!(receive(&mut receiver, &all), vec![frame]);
+    let minute = receiver.stats.last_minute();
+    assert_eq!(minute.noise, 39);
+    assert_eq!(minute.bit_error_rate(), Some(0));
+}
+
+#[test]
+fn pulses_of_the_minute_gap() {
+    let mut decoder = PulseDecoder::new();
+    let at = Instant::from_millis;
+    decoder.on_edge(true, at(0));
+    decoder.on_edge(false, at(100));
+    decoder.on_edge(true, at(2000));
+    assert_eq!(
+        decoder.on_edge(false, at(2200)),
+        Some(Pulse::LastBit(false))
+    );
+}
+
+#[test]
+fn decodes_the_utc_time() {
+    // Both sides of the end of summer time
+    for &(min, zone) in [(58, Zone::Cest), (7, Zone::Cet)].iter() {
+        let utc = timezone::to_utc(&local(min), Some(zone)).unwrap();
+        assert_eq!(decode(&encode(&local(min), zone)), Ok(utc));
+    }
+}
+
+#[test]
+fn receives_a_leap_second_minute() {
+    let mut announced = encode(&local(58), Zone::Cest);
+    announced.0 |= 1 << 19;
+    // The parity does not cover A2
+    assert_eq!(decode(&announced), decode(&encode(&local(58), Zone::Cest)));
+    let leap = encode(&local(59), Zone::Cest);
+
+    let mut all = vec![
+        (true, Instant::from_millis(58_000)),
+        (false, Instant::from_millis(58_100)),
+    ];
+    all.extend(edges(&announced, 60_000));
+    all.extend(edges(&leap, 120_000));
+    // The leap second is sent as a 0 before the minute gap
+    all.push((true, Instant::from_millis(179_000)));
+    all.push((false, Instant::from_millis(179_100)));
+    all.push((true, Instant::from_millis(181_000)));
+    all.push((false, Instant::from_millis(181_100)));
+
+    let mut receiver = Receiver::new();
+    assert_eq!(receive(&mut receiver, &all), vec![announced, leap]);
+    assert_eq!(receiver.stats.last_minute().pulses, 60);
+}
+
+#[test]
+fn jumps_do_not_lower_the_quality() {
+    let frames: Vec<Frame> = (10..15).map(|min| encode(&local(min), Zone::Cet)).collect();
+    let mut all = vec![
+        (true, Instant::from_millis(58_000)),
+        (false, Instant::from_millis(58_100)),
+    ];
+    for (index, frame) in frames.iter().enumerate() {
+        all.extend(edges(frame, 60_000 * (index as u32 + 1)));
+    }
+    all.push((true, Instant::from_millis(360_000)));
+    all.push((false, Instant::from_millis(360_100)));
+
+    let clock = Time::new(2021, 1, 1, 12, 0, 0).unwrap();
+    let mut receiver = Receiver::new();
+    let mut validator = Validator::new(2, Duration::from_secs(120));
+    let mut checked = Vec::new();
+    for &(high, at) in all.iter() {
+        if let Some(frame) = receiver.on_edge(high, at) {
+            let decoded = receiver.decode(frame);
+            checked.push(validator.check(decoded, Some(&clock)));
+        }
+    }
+
+    // The partial minute before the first frame
+    assert_eq!(checked[0], Err(TimeError::Incomplete));
+    for jump in &checked[1..5] {
+        assert_eq!(*jump, Err(TimeError::ImplausibleJump));
+    }
+    let utc = timezone::to_utc(&local(14), Some(Zone::Cet));
+    assert_eq!(checked[5], Ok(utc));
+    assert_eq!((receiver.stats.frames(), receiver.stats.failed()), (5, 1));
+    assert_eq!(receiver.stats.bars(Instant::from_millis(360_100)), 4);
+
+    // A frame with a parity error is rejected
+    let mut broken = encode(&local(15), Zone::Cet);
+    broken.0 ^= 1 << 21;
+    let mut all = edges(&broken, 360_000);
+    all.push((true, Instant::from_millis(420_000)));
+    all.push((false, Instant::from_millis(420_100)));
+    let frame = all
+        .iter()
+        .find_map(|(high, at)| receiver.on_edge(*high, *at))
+        .unwrap();
+    assert!(receiver.decode(frame).is_err());
+    assert_eq!((receiver.stats.frames(), receiver.stats.failed()), (5, 2));
+    assert!(receiver.stats.bars(Instant::from_millis(420_100)) < 4);
+}
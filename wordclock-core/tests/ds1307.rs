@@ -0,0 +1,144 @@
+use embedded_hal::blocking::i2c::Write;
+use wordclock_core::emulator::ds1307::{Ds1307Chip, CONTROL, RAM_START};
+use wordclock_core::rtc::ds1307::TIME_SET;
+use wordclock_core::rtc::{Ds1307, Rtc, ADDRESS};
+use wordclock_core::time::{Field, Time, TimeError};
+
+// Flags of the hours register
+const TWELVE_HOURS: u8 = 0b0100_0000;
+const PM: u8 = 0b0010_0000;
+
+fn rtc(chip: &Ds1307Chip) -> Ds1307<Ds1307Chip> {
+    Ds1307::new(chip.clone())
+}
+
+/// Chip with the time set by the driver earlier
+fn chip_with_time(registers: [u8; 7]) -> Ds1307Chip {
+    let chip = Ds1307Chip::new();
+    chip.set_time_registers(registers);
+    chip.set_register(RAM_START, TIME_SET);
+    chip
+}
+
+#[test]
+fn reads_the_time_in_24_hour_mode() {
+    let chip = chip_with_time([0x45, 0x59, 0x23, 5, 0x31, 0x12, 0x21]);
+    assert_eq!(
+        rtc(&chip).time(),
+        Ok(Time::new(2021, 12, 31, 23, 59, 45).unwrap())
+    );
+}
+
+#[test]
+fn reads_the_time_in_12_hour_mode() {
+    let hour = |register: u8| {
+        let chip = chip_with_time([0x00, 0x30, register, 1, 0x01, 0x03, 0x21]);
+        rtc(&chip).time().map(|time| time.hour())
+    };
+    assert_eq!(hour(TWELVE_HOURS | 0x09), Ok(9));
+    assert_eq!(hour(TWELVE_HOURS | PM | 0x09), Ok(21));
+    assert_eq!(hour(TWELVE_HOURS | 0x12), Ok(0));
+    assert_eq!(hour(TWELVE_HOURS | PM | 0x12), Ok(12));
+}
+
+#[test]
+fn switches_12_hour_mode_to_24_hours() {
+    let chip = chip_with_time([0x00, 0x30, TWELVE_HOURS | PM | 0x12, 1, 0x01, 0x03, 0x21]);
+    rtc(&chip).time().unwrap();
+    assert_eq!(chip.time_registers()[2], 0x12);
+
+    let chip = chip_with_time([0x00, 0x30, TWELVE_HOURS | 0x12, 1, 0x01, 0x03, 0x21]);
+    rtc(&chip).time().unwrap();
+    assert_eq!(chip.time_registers()[2], 0x00);
+}
+
+#[test]
+fn halted_clock_is_not_set_and_restarted() {
+    let chip = Ds1307Chip::new();
+    assert!(chip.halted());
+
+    assert_eq!(rtc(&chip).time(), Err(TimeError::NotSet));
+    assert!(!chip.halted());
+    // Running again, but still at the time of the power up
+    assert_eq!(rtc(&chip).time(), Err(TimeError::NotSet));
+}
+
+#[test]
+fn time_without_marker_is_not_set() {
+    let chip = chip_with_time([0x45, 0x59, 0x23, 5, 0x31, 0x12, 0x21]);
+    chip.set_register(RAM_START, 0xff);
+    assert_eq!(rtc(&chip).time(), Err(TimeError::NotSet));
+}
+
+#[test]
+fn writes_the_time_in_bcd_and_starts_the_oscillator() {
+    let chip = Ds1307Chip::new();
+    assert!(chip.halted());
+
+    let time = Time::new(2024, 2, 29, 18, 7, 3).unwrap();
+    rtc(&chip).set_time(&time).unwrap();
+
+    assert_eq!(
+        chip.time_registers(),
+        [0x03, 0x07, 0x18, 4, 0x29, 0x02, 0x24]
+    );
+    assert!(!chip.halted());
+    assert_eq!(rtc(&chip).time(), Ok(time));
+}
+
+#[test]
+fn setting_the_time_keeps_control_and_ram() {
+    let chip = Ds1307Chip::new();
+    chip.set_register(CONTROL, 0x10);
+    chip.set_register(RAM_START + 1, 0xa5);
+
+    rtc(&chip)
+        .set_time(&Time::new(2021, 6, 1, 12, 0, 0).unwrap())
+        .unwrap();
+
+    assert_eq!(chip.register(CONTROL), 0x10);
+    assert_eq!(chip.register(RAM_START + 1), 0xa5);
+}
+
+#[test]
+fn enables_the_1hz_square_wave() {
+    let chip = Ds1307Chip::new();
+    assert!(!chip.square_wave_enabled());
+    rtc(&chip).enable_square_wave().unwrap();
+    assert!(chip.square_wave_enabled());
+    // Rate select bits cleared for 1 Hz
+    assert_eq!(chip.register(CONTROL) & 0b11, 0);
+}
+
+#[test]
+fn missing_chip_is_a_bus_error() {
+    let chip = Ds1307Chip::new();
+    chip.set_present(false);
+    let mut rtc = rtc(&chip);
+    let time = Time::new(2021, 6, 1, 12, 0, 0).unwrap();
+
+    assert_eq!(rtc.time(), Err(TimeError::Bus));
+    assert_eq!(rtc.set_time(&time), Err(TimeError::Bus));
+    assert_eq!(rtc.enable_square_wave(), Err(TimeError::Bus));
+    assert!(chip.halted());
+}
+
+#[test]
+fn invalid_registers_name_the_field() {
+    let chip = chip_with_time([0x00, 0x00, 0x25, 1, 0x01, 0x01, 0x21]);
+    assert_eq!(rtc(&chip).time(), Err(TimeError::InvalidField(Field::Hour)));
+
+    chip.set_time_registers([0x00, 0x00, 0x12, 1, 0x01, 0x1a, 0x21]);
+    assert_eq!(
+        rtc(&chip).time(),
+        Err(TimeError::InvalidField(Field::Month))
+    );
+}
+
+#[test]
+fn register_pointer_wraps_after_the_ram() {
+    let mut chip = Ds1307Chip::new();
+    chip.write(ADDRESS, &[0x3f, 0x11, 0x22]).unwrap();
+    assert_eq!(chip.register(0x3f), 0x11);
+    assert_eq!(chip.register(0x00), 0x22);
+}
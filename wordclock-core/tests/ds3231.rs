@@ -0,0 +1,22 @@
+use wordclock_core::emulator::ds1307::Ds1307Chip;
+use wordclock_core::rtc::{Ds3231, Rtc};
+use wordclock_core::time::{Time, TimeError};
+
+// The time registers are laid out like on the DS1307, so its emulation
+// serves as the register file
+const STATUS: u8 = 0x0f;
+const OSCILLATOR_STOPPED: u8 = 0b1000_0000;
+const EN32KHZ: u8 = 0b0000_1000;
+
+#[test]
+fn setting_the_time_clears_only_the_oscillator_stop_flag() {
+    let chip = Ds1307Chip::new();
+    chip.set_register(STATUS, OSCILLATOR_STOPPED | EN32KHZ);
+    let mut rtc = Ds3231::new(chip.clone());
+    assert_eq!(rtc.time(), Err(TimeError::NotSet));
+
+    let time = Time::new(2021, 3, 1, 12, 34, 56).unwrap();
+    rtc.set_time(&time).unwrap();
+    assert_eq!(chip.register(STATUS), EN32KHZ);
+    assert_eq!(rtc.time(), Ok(time));
+}
@@ -0,0 +1,131 @@
+//! Compares the words shown for every minute of the day with a checked in
+//! table per language.
+//!
+//! Run with `UPDATE_GOLDEN=1` to rewrite the tables after an intended change
+//! of the phrasing and review the result as a diff.
+
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use wordclock_core::display::{corner, DisplayBuffer};
+use wordclock_core::emulator::shift_register::ShiftRegister;
+use wordclock_core::language::german::{Dialect, One, Quarter, Twenty};
+use wordclock_core::language::{Dutch, English, German, Language};
+use wordclock_core::phrase::{phrase, Rounding};
+use wordclock_core::time::Time;
+
+const CORNERS: [(usize, u16); 4] = [
+    corner::TOP_LEFT,
+    corner::TOP_RIGHT,
+    corner::BOTTOM_LEFT,
+    corner::BOTTOM_RIGHT,
+];
+
+/// Turns the buffer back into words and a marker for every corner LED
+fn decode<L: Language>(language: &L, data: &DisplayBuffer) -> String {
+    // LEDs are active low
+    let mut lit: Vec<u16> = data.iter().map(|row| !row).collect();
+
+    // Match longer words first, shorter ones may share their letters
+    let mut candidates = language.all_words().to_vec();
+    candidates.sort_by_key(|word| std::cmp::Reverse(word.letters.chars().count()));
+    let mut found = Vec::new();
+    for word in candidates {
+        if lit[word.row] & word.mask == word.mask {
+            found.push((word.row, word.mask.leading_zeros(), word.letters));
+            lit[word.row] &= !word.mask;
+        }
+    }
+    // Reading order
+    found.sort();
+    let text: Vec<&str> = found.iter().map(|(_, _, letters)| *letters).collect();
+
+    let mut corners = String::new();
+    for (row, mask) in CORNERS.iter() {
+        if lit[*row] & mask != 0 {
+            corners.push('*');
+            lit[*row] &= !mask;
+        } else {
+            corners.push('.');
+        }
+    }
+
+    for (row, leds) in lit.iter().enumerate() {
+        assert_eq!(
+            *leds, 0,
+            "LEDs {:016b} in row {} belong to no word",
+            leds, row
+        );
+    }
+
+    format!("{:<36} {}", text.join(" "), corners)
+}
+
+fn check_all_minutes<L: Language>(language: &L, rounding: Rounding, name: &str) {
+    let mut display = ShiftRegister::new().display();
+
+    let mut table = String::new();
+    for hour in 0..24 {
+        for min in 0..60 {
+            let time = Time::new(2021, 1, 1, hour, min, 0).unwrap();
+            display.show(&phrase(language, &time, rounding, true));
+            writeln!(
+                table,
+                "{:02}:{:02} {}",
+                hour,
+                min,
+                decode(language, &display.data)
+            )
+            .unwrap();
+        }
+    }
+
+    let path: PathBuf = [
+        env!("CARGO_MANIFEST_DIR"),
+        "tests",
+        "golden",
+        &format!("{}.txt", name),
+    ]
+    .iter()
+    .collect();
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, &table).unwrap();
+    }
+    let golden = std::fs::read_to_string(&path).unwrap();
+    for (line, (expected, actual)) in golden.lines().zip(table.lines()).enumerate() {
+        assert_eq!(expected, actual, "line {} of {}", line + 1, path.display());
+    }
+    assert_eq!(golden.lines().count(), table.lines().count());
+}
+
+#[test]
+fn german() {
+    check_all_minutes(&German::default(), Rounding::Floor, "german");
+}
+
+#[test]
+fn german_nearest() {
+    check_all_minutes(&German::default(), Rounding::Nearest, "german-nearest");
+}
+
+#[test]
+fn german_regional() {
+    let german = German {
+        dialect: Dialect {
+            quarter: Quarter::Dreiviertel,
+            twenty: Twenty::ZehnHalb,
+            one: One::Ein,
+        },
+    };
+    check_all_minutes(&german, Rounding::Floor, "german-regional");
+}
+
+#[test]
+fn english() {
+    check_all_minutes(&English, Rounding::Floor, "english");
+}
+
+#[test]
+fn dutch() {
+    check_all_minutes(&Dutch, Rounding::Floor, "dutch");
+}
@@ -0,0 +1,71 @@
+use wordclock_core::display::{corner, BLANK};
+use wordclock_core::emulator::shift_register::ShiftRegister;
+use wordclock_core::language::german::words;
+
+#[test]
+fn every_latch_carries_32_bits() {
+    let shift_register = ShiftRegister::new();
+    let mut display = shift_register.display();
+    display.data = [0x0000; 10];
+    display.update_display();
+
+    let latched = shift_register.take_latched();
+    assert_eq!(latched.len(), 10);
+    assert!(latched.iter().all(|latch| latch.bits.len() == 32));
+    assert_eq!(shift_register.pending_bits(), 0);
+}
+
+#[test]
+fn columns_are_shifted_lsb_first_before_row_select() {
+    let shift_register = ShiftRegister::new();
+    let mut display = shift_register.display();
+    display.data = BLANK;
+    display.data[4] = 0b1010_0000_1100_0001;
+    display.update_display();
+
+    let latched = shift_register.take_latched();
+    assert_eq!(latched.len(), 1);
+    let latch = &latched[0];
+
+    let expected_columns: Vec<bool> = (0..16)
+        .map(|bit| display.data[4] & (1 << bit) != 0)
+        .collect();
+    let expected_rows: Vec<bool> = (0..16).map(|bit| bit == 4).collect();
+    assert_eq!(latch.bits[..16], expected_columns[..]);
+    assert_eq!(latch.bits[16..], expected_rows[..]);
+    assert_eq!(latch.columns(), display.data[4]);
+    assert_eq!(latch.selected_row(), Some(4));
+}
+
+#[test]
+fn blank_rows_are_skipped() {
+    let shift_register = ShiftRegister::new();
+    let mut display = shift_register.display();
+    display.clear();
+    display.set_word(&words::ES);
+    display.set_line(corner::BOTTOM_LEFT);
+    display.set_word(&words::UHR);
+    display.update_display();
+
+    let latched = shift_register.take_latched();
+    let rows: Vec<_> = latched.iter().map(|latch| latch.selected_row()).collect();
+    assert_eq!(rows, [Some(0), Some(2), Some(9)]);
+    for latch in latched {
+        let row = latch.selected_row().unwrap();
+        assert_eq!(latch.columns(), display.data[row]);
+    }
+}
+
+#[test]
+fn output_enable_is_active_low() {
+    let shift_register = ShiftRegister::new();
+    let mut display = shift_register.display();
+
+    display.enable_output();
+    assert!(shift_register.output_enabled());
+    assert!(display.enabled());
+
+    display.disable_output();
+    assert!(!shift_register.output_enabled());
+    assert!(!display.enabled());
+}
@@ -0,0 +1,204 @@
+//! Terminal preview of the wordclock
+//!
+//! Shows the letters that the firmware would light for a given time or runs
+//! the clock from the system time, optionally accelerated.
+
+use std::process;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use wordclock_core::emulator::shift_register::ShiftRegister;
+use wordclock_core::language::german::{Dialect, One, Quarter, Twenty};
+use wordclock_core::language::{Dutch, English, German, Language};
+use wordclock_core::phrase::{phrase, Rounding};
+use wordclock_core::time::Time;
+
+mod render;
+
+const USAGE: &str = "\
+usage: wordclock-sim [OPTIONS] [HH:MM]
+
+Renders the letters of the wordclock for the given time, or for the time of
+the system clock if no time is given.
+
+options:
+    --live              keep running and update the display every second
+    --speed FACTOR      run the clock FACTOR times faster than real time,
+                        at most 86400 (a day per second), implies --live
+    --utc-offset HOURS  offset of the local time to UTC used with the system
+                        clock, defaults to 0
+    --language LANG     front plate to show, one of german (default),
+                        english or dutch
+    --dreiviertel       german: \"viertel drei\" and \"dreiviertel drei\"
+    --zehn-halb         german: \"zehn vor halb\" instead of \"zwanzig nach\"
+    --ein-uhr           german: \"ein uhr\" instead of \"eins uhr\"
+    --nearest           round to the nearest phrase instead of down
+    --no-corners        do not show the minutes in the corners
+    --no-color          mark unlit letters with dots instead of colors
+    -h, --help          show this help";
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+/// Fastest speed, a day per second
+const MAX_SPEED: u64 = SECONDS_PER_DAY;
+
+struct Options {
+    start: Option<u64>,
+    live: bool,
+    speed: u64,
+    utc_offset: i64,
+    language: String,
+    dialect: Dialect,
+    rounding: Rounding,
+    corners: bool,
+    color: bool,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        start: None,
+        live: false,
+        speed: 1,
+        utc_offset: 0,
+        language: "german".into(),
+        dialect: Dialect::default(),
+        rounding: Rounding::Floor,
+        corners: true,
+        color: true,
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            "--live" => options.live = true,
+            "--no-color" => options.color = false,
+            "--speed" => {
+                options.speed = value(&mut args, &arg)?;
+                if options.speed == 0 || options.speed > MAX_SPEED {
+                    return Err(format!("{} has to be from 1 to {}", arg, MAX_SPEED));
+                }
+                options.live = true;
+            }
+            "--utc-offset" => options.utc_offset = value(&mut args, &arg)?,
+            "--language" => options.language = value(&mut args, &arg)?,
+            "--dreiviertel" => options.dialect.quarter = Quarter::Dreiviertel,
+            "--zehn-halb" => options.dialect.twenty = Twenty::ZehnHalb,
+            "--ein-uhr" => options.dialect.one = One::Ein,
+            "--nearest" => options.rounding = Rounding::Nearest,
+            "--no-corners" => options.corners = false,
+            time => options.start = Some(parse_time(time)?),
+        }
+    }
+
+    Ok(options)
+}
+
+fn value<T: std::str::FromStr>(
+    args: &mut impl Iterator<Item = String>,
+    name: &str,
+) -> Result<T, String> {
+    let value = args
+        .next()
+        .ok_or_else(|| format!("{} needs a value", name))?;
+    value
+        .parse()
+        .map_err(|_| format!("invalid value {:?} for {}", value, name))
+}
+
+fn language(options: &Options) -> Result<Box<dyn Language>, String> {
+    match options.language.as_str() {
+        "german" => Ok(Box::new(German {
+            dialect: options.dialect,
+        })),
+        "english" => Ok(Box::new(English)),
+        "dutch" => Ok(Box::new(Dutch)),
+        name => Err(format!("unknown language {:?}", name)),
+    }
+}
+
+/// Parses `HH:MM` into seconds of the day
+fn parse_time(time: &str) -> Result<u64, String> {
+    let invalid = || format!("invalid time {:?}, expected HH:MM", time);
+    let mut parts = time.splitn(2, ':');
+    let hour: u64 = parts
+        .next()
+        .and_then(|h| h.parse().ok())
+        .ok_or_else(invalid)?;
+    let min: u64 = parts
+        .next()
+        .and_then(|m| m.parse().ok())
+        .ok_or_else(invalid)?;
+    if hour >= 24 || min >= 60 {
+        return Err(invalid());
+    }
+    Ok(hour * 3600 + min * 60)
+}
+
+/// Seconds of the current day of the system clock shifted by the offset
+fn system_time(utc_offset: i64) -> u64 {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64;
+    (now + utc_offset * 3600).rem_euclid(SECONDS_PER_DAY as i64) as u64
+}
+
+fn to_time(seconds: u64) -> Time {
+    let seconds = seconds % SECONDS_PER_DAY;
+    Time::new(
+        2021,
+        1,
+        1,
+        (seconds / 3600) as u8,
+        (seconds / 60 % 60) as u8,
+        (seconds % 60) as u8,
+    )
+    .expect("seconds of the day are a valid time")
+}
+
+fn main() {
+    let (options, language) = match parse_args(std::env::args().skip(1)).and_then(|options| {
+        let language = language(&options)?;
+        Ok((options, language))
+    }) {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, USAGE);
+            process::exit(1);
+        }
+    };
+
+    let renderer = render::Renderer {
+        letters: language.letters(),
+        color: options.color,
+    };
+    let mut display = ShiftRegister::new().display();
+    let start = options
+        .start
+        .unwrap_or_else(|| system_time(options.utc_offset));
+    let started = Instant::now();
+
+    loop {
+        let time = to_time(start + started.elapsed().as_secs() * options.speed);
+        display.show(&phrase(
+            language.as_ref(),
+            &time,
+            options.rounding,
+            options.corners,
+        ));
+
+        if options.live {
+            // Clear the terminal and move the cursor home
+            print!("\x1b[2J\x1b[H");
+        }
+        println!("{:02}:{:02}\n", time.hour(), time.minutes());
+        print!("{}", renderer.render(&display.data));
+
+        if !options.live {
+            break;
+        }
+        thread::sleep(Duration::from_millis(1000 / options.speed.clamp(1, 10)));
+    }
+}
@@ -0,0 +1,99 @@
+//! Draws the letter matrix and the corner LEDs to the terminal
+
+use wordclock_core::display::{corner, DisplayBuffer};
+
+const LIT: &str = "\x1b[1;93m";
+const UNLIT: &str = "\x1b[2;37m";
+const RESET: &str = "\x1b[0m";
+
+/// The LEDs are active low, a cleared bit lights the LED
+fn is_lit(data: &DisplayBuffer, line: (usize, u16)) -> bool {
+    data[line.0] & line.1 == 0
+}
+
+/// Column 0 is the most significant bit of a row
+fn letter_mask(column: usize) -> u16 {
+    0x8000 >> column
+}
+
+pub struct Renderer {
+    pub letters: &'static [&'static str; 10],
+    pub color: bool,
+}
+
+impl Renderer {
+    /// Renders the grid with one line of corner LEDs above and below
+    pub fn render(&self, data: &DisplayBuffer) -> String {
+        let mut out = String::new();
+        self.push_corners(&mut out, data, corner::TOP_LEFT, corner::TOP_RIGHT);
+        for (row, letters) in self.letters.iter().enumerate() {
+            out.push_str("  ");
+            for (column, letter) in letters.chars().enumerate() {
+                let lit = is_lit(data, (row, letter_mask(column)));
+                out.push(' ');
+                self.push_letter(&mut out, letter, lit);
+            }
+            out.push('\n');
+        }
+        self.push_corners(&mut out, data, corner::BOTTOM_LEFT, corner::BOTTOM_RIGHT);
+        out
+    }
+
+    fn push_corners(
+        &self,
+        out: &mut String,
+        data: &DisplayBuffer,
+        left: (usize, u16),
+        right: (usize, u16),
+    ) {
+        self.push_letter(out, '●', is_lit(data, left));
+        out.push_str(&" ".repeat(2 * self.letters[0].chars().count() + 2));
+        self.push_letter(out, '●', is_lit(data, right));
+        out.push('\n');
+    }
+
+    fn push_letter(&self, out: &mut String, letter: char, lit: bool) {
+        match (self.color, lit) {
+            (true, true) => {
+                out.push_str(LIT);
+                out.push(letter);
+                out.push_str(RESET);
+            }
+            (true, false) => {
+                out.push_str(UNLIT);
+                out.push(letter);
+                out.push_str(RESET);
+            }
+            (false, true) => out.push(letter),
+            (false, false) => out.push('·'),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wordclock_core::display::{set_line, set_word, BLANK};
+    use wordclock_core::language::german::words;
+
+    #[test]
+    fn plain_output_shows_only_lit_letters() {
+        let mut data = BLANK;
+        set_word(&mut data, &words::ES);
+        set_word(&mut data, &words::IST);
+        set_word(&mut data, &words::UHR);
+        set_line(&mut data, corner::BOTTOM_RIGHT);
+
+        let out = Renderer {
+            letters: &words::LETTERS,
+            color: false,
+        }
+        .render(&data);
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 12);
+        assert_eq!(lines[0], "·                        ·");
+        assert_eq!(lines[1], "   E S · I S T · · · · ·");
+        assert_eq!(lines[10], "   · · · · · · · · U H R");
+        assert_eq!(lines[11], "·                        ●");
+    }
+}